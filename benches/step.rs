@@ -0,0 +1,47 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use gol_rs::{bitboard::BitBoard, board::Board, soup::Soup};
+
+const SIZES: [usize; 5] = [50, 256, 1024, 2048, 4096];
+
+fn step(c: &mut Criterion) {
+    let mut group = c.benchmark_group("step");
+    group.sample_size(10);
+    for size in SIZES {
+        // The same half full soup every run, so results compare across runs
+        let mut naive = Board::new(size, size);
+        for (i, alive) in Soup::default().cells(size, size).into_iter().enumerate() {
+            naive.set(i % size, i / size, alive);
+        }
+        let mut packed = BitBoard::new(size, size);
+        for (x, y) in naive.live_cells() {
+            packed.set(x, y, true);
+        }
+        let mut naive_parallel = Board::new(size, size);
+        let mut packed_parallel = BitBoard::new(size, size);
+        for (x, y) in naive.live_cells() {
+            naive_parallel.set(x, y, true);
+            packed_parallel.set(x, y, true);
+        }
+        naive_parallel.set_parallel(true);
+        packed_parallel.set_parallel(true);
+
+        group.bench_with_input(BenchmarkId::new("naive", size), &size, |b, _| {
+            b.iter(|| naive.step())
+        });
+        group.bench_with_input(BenchmarkId::new("bit-packed", size), &size, |b, _| {
+            b.iter(|| packed.step())
+        });
+        group.bench_with_input(BenchmarkId::new("naive-parallel", size), &size, |b, _| {
+            b.iter(|| naive_parallel.step())
+        });
+        group.bench_with_input(
+            BenchmarkId::new("bit-packed-parallel", size),
+            &size,
+            |b, _| b.iter(|| packed_parallel.step()),
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, step);
+criterion_main!(benches);
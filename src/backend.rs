@@ -0,0 +1,107 @@
+use crate::{
+    bitboard::BitBoard, board::Board, engine::Engine, hashlife::HashLife, sparse::SparseBoard,
+};
+
+pub const HASHLIFE_MEMORY_BUDGET: usize = 256 * 1024 * 1024;
+// Largest 2^k jump on engines that step one generation at a time
+pub const MAX_STEP_LOG: u8 = 12;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Backend {
+    Dense,
+    Packed,
+    Sparse,
+    HashLife,
+}
+
+impl Backend {
+    pub const ALL: [Backend; 4] = [
+        Backend::Dense,
+        Backend::Packed,
+        Backend::Sparse,
+        Backend::HashLife,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Backend::Dense => "Dense",
+            Backend::Packed => "Bit-packed",
+            Backend::Sparse => "Sparse",
+            Backend::HashLife => "HashLife",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Backend::ALL
+            .into_iter()
+            .find(|backend| backend.name().eq_ignore_ascii_case(name))
+    }
+
+    pub fn bounded(self) -> bool {
+        matches!(self, Backend::Dense | Backend::Packed)
+    }
+
+    // The unbounded engines ignore the size, only HashLife uses the memory budget
+    pub fn create(self, cols: usize, rows: usize, hashlife_budget: usize) -> Box<dyn Engine> {
+        match self {
+            Backend::Dense => Box::new(Board::new(cols, rows)),
+            Backend::Packed => Box::new(BitBoard::new(cols, rows)),
+            Backend::Sparse => Box::new(SparseBoard::new()),
+            Backend::HashLife => Box::new(HashLife::new(hashlife_budget)),
+        }
+    }
+
+    // HashLife covers 2^k generations in about the time of k steps, the others
+    // run every generation
+    pub fn max_step_log(self) -> u8 {
+        match self {
+            Backend::HashLife => HashLife::MAX_STEP_LOG,
+            _ => MAX_STEP_LOG,
+        }
+    }
+}
+
+// Board size as COLSxROWS, both above zero
+pub fn parse_size(arg: &str) -> Option<[usize; 2]> {
+    let (cols, rows) = arg.split_once('x')?;
+    let cols = cols.parse().ok().filter(|&n| n > 0)?;
+    let rows = rows.parse().ok().filter(|&n| n > 0)?;
+    Some([cols, rows])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_sizes() {
+        assert_eq!(parse_size("120x80"), Some([120, 80]));
+        assert_eq!(parse_size("0x80"), None);
+        assert_eq!(parse_size("120x"), None);
+        assert_eq!(parse_size("120"), None);
+    }
+
+    #[test]
+    fn finds_backends_by_name() {
+        for backend in Backend::ALL {
+            assert_eq!(
+                Backend::from_name(&backend.name().to_lowercase()),
+                Some(backend)
+            );
+        }
+        assert_eq!(Backend::from_name("quadtree"), None);
+    }
+
+    #[test]
+    fn creates_bounded_engines_only_for_bounded_backends() {
+        for backend in Backend::ALL {
+            let engine = backend.create(10, 20, 1 << 20);
+            let expected = if backend.bounded() {
+                Some([10, 20])
+            } else {
+                None
+            };
+            assert_eq!(engine.size(), expected, "{}", backend.name());
+        }
+    }
+}
@@ -0,0 +1,371 @@
+use rayon::prelude::*;
+
+use crate::{
+    board::{band_rows, Anchor},
+    rule::Rule,
+    topology::Topology,
+};
+
+// Bounded board packed 64 cells to a u64. Every row has a ghost cell at each end and
+// there is a ghost row above and below, filled from the topology before each step so
+// the kernel never has to special case the edges.
+pub struct BitBoard {
+    cols: usize,
+    rows: usize,
+    words: usize,
+    cells: Vec<u64>,
+    compute: Vec<u64>,
+    rule: Rule,
+    topology: Topology,
+    parallel: bool,
+}
+
+impl BitBoard {
+    pub fn new(cols: usize, rows: usize) -> Self {
+        let words = (cols + 2).div_ceil(64);
+        Self {
+            cols,
+            rows,
+            words,
+            cells: vec![0; words * (rows + 2)],
+            compute: vec![0; words * (rows + 2)],
+            rule: Rule::default(),
+            topology: Topology::default(),
+            parallel: false,
+        }
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn rule(&self) -> Rule {
+        self.rule
+    }
+
+    // Padded coordinates, so the board itself starts at (1, 1)
+    fn get_padded(&self, x: usize, y: usize) -> bool {
+        self.cells[y * self.words + x / 64] >> (x % 64) & 1 == 1
+    }
+
+    fn set_padded(&mut self, x: usize, y: usize, alive: bool) {
+        let word = &mut self.cells[y * self.words + x / 64];
+        if alive {
+            *word |= 1 << (x % 64);
+        } else {
+            *word &= !(1 << (x % 64));
+        }
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> bool {
+        self.get_padded(x + 1, y + 1)
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, alive: bool) {
+        self.set_padded(x + 1, y + 1, alive);
+    }
+
+    pub fn live_cells(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        (1..=self.rows).flat_map(move |y| {
+            let row = &self.cells[y * self.words..(y + 1) * self.words];
+            row.iter().enumerate().flat_map(move |(w, &word)| {
+                let mut word = word & self.interior_mask(w);
+                std::iter::from_fn(move || {
+                    if word == 0 {
+                        return None;
+                    }
+                    let bit = word.trailing_zeros() as usize;
+                    word &= word - 1;
+                    Some((w * 64 + bit - 1, y - 1))
+                })
+            })
+        })
+    }
+
+    // Bits of word w that hold real cells rather than ghosts or padding
+    fn interior_mask(&self, w: usize) -> u64 {
+        let mut mask = !0u64;
+        if w == 0 {
+            mask &= !1;
+        }
+        let first = w * 64;
+        let end = self.cols + 1;
+        if end < first + 64 {
+            mask &= if end > first {
+                (1u64 << (end - first)) - 1
+            } else {
+                0
+            };
+        }
+        mask
+    }
+
+    fn fill_ghosts(&mut self) {
+        let (cols, rows) = (self.cols as i64, self.rows as i64);
+        for y in 0..rows {
+            for x in [-1, cols] {
+                let alive = self.wrapped(x, y);
+                self.set_padded((x + 1) as usize, (y + 1) as usize, alive);
+            }
+        }
+        for x in -1..=cols {
+            for y in [-1, rows] {
+                let alive = self.wrapped(x, y);
+                self.set_padded((x + 1) as usize, (y + 1) as usize, alive);
+            }
+        }
+    }
+
+    fn wrapped(&self, x: i64, y: i64) -> bool {
+        self.topology
+            .wrap(x, y, self.cols, self.rows)
+            .is_some_and(|(x, y)| self.get(x, y))
+    }
+
+    pub fn step(&mut self) {
+        self.fill_ghosts();
+        let words = self.words;
+        let table = RuleTable::new(&self.rule);
+        let mut compute = std::mem::take(&mut self.compute);
+        let interior = &mut compute[words..(self.rows + 1) * words];
+        if self.parallel {
+            let band = band_rows(self.rows);
+            interior
+                .par_chunks_mut(band * words)
+                .enumerate()
+                .for_each(|(i, out)| self.step_rows(&table, 1 + i * band, out));
+        } else {
+            self.step_rows(&table, 1, interior);
+        }
+        self.compute = std::mem::replace(&mut self.cells, compute);
+    }
+
+    // Computes the next state of the padded rows starting at first into out
+    fn step_rows(&self, table: &RuleTable, first: usize, out: &mut [u64]) {
+        for (i, word) in out.iter_mut().enumerate() {
+            let w = i % self.words;
+            let y = first + i / self.words;
+            let above = self.neighbourhood(y - 1, w);
+            let row = self.neighbourhood(y, w);
+            let below = self.neighbourhood(y + 1, w);
+            *word = table.apply(row[1], above, row, below) & self.interior_mask(w);
+        }
+    }
+
+    // Word w of row y alongside the same word shifted so each bit holds its west and
+    // east neighbour: [west, centre, east]
+    fn neighbourhood(&self, y: usize, w: usize) -> [u64; 3] {
+        let row = &self.cells[y * self.words..(y + 1) * self.words];
+        let centre = row[w];
+        let before = if w > 0 { row[w - 1] } else { 0 };
+        let after = if w + 1 < self.words { row[w + 1] } else { 0 };
+        [
+            (centre << 1) | (before >> 63),
+            centre,
+            (centre >> 1) | (after << 63),
+        ]
+    }
+
+    pub fn resize(&mut self, cols: usize, rows: usize, anchor: Anchor) {
+        let mut board = BitBoard::new(cols, rows);
+        let from = [self.cols, self.rows];
+        for (x, y) in anchor.move_cells(self.live_cells(), from, [cols, rows]) {
+            board.set(x, y, true);
+        }
+        board.rule = self.rule;
+        board.topology = self.topology;
+        board.parallel = self.parallel;
+        *self = board;
+    }
+
+    pub fn set_rule(&mut self, rule: Rule) {
+        self.rule = rule;
+    }
+
+    pub fn set_topology(&mut self, topology: Topology) {
+        self.topology = topology;
+    }
+
+    pub fn set_parallel(&mut self, parallel: bool) {
+        self.parallel = parallel;
+    }
+
+    pub fn population(&self) -> u64 {
+        (1..=self.rows)
+            .map(|y| {
+                let row = &self.cells[y * self.words..(y + 1) * self.words];
+                row.iter()
+                    .enumerate()
+                    .map(|(w, &word)| (word & self.interior_mask(w)).count_ones() as u64)
+                    .sum::<u64>()
+            })
+            .sum()
+    }
+
+    pub fn clear(&mut self) {
+        self.cells.fill(0);
+    }
+}
+
+// Bit-sliced lookup of which neighbour counts give a live cell
+struct RuleTable {
+    // For each count, all ones when a dead cell is born / a live cell survives
+    birth: [u64; 9],
+    survival: [u64; 9],
+}
+
+impl RuleTable {
+    fn new(rule: &Rule) -> Self {
+        let mut table = Self {
+            birth: [0; 9],
+            survival: [0; 9],
+        };
+        for n in 0..9 {
+            if rule.birth(n as u32) {
+                table.birth[n] = !0;
+            }
+            if rule.survival(n as u32) {
+                table.survival[n] = !0;
+            }
+        }
+        table
+    }
+
+    fn apply(&self, alive: u64, above: [u64; 3], row: [u64; 3], below: [u64; 3]) -> u64 {
+        // Adder tree summing the eight neighbours into a 4 bit count per cell
+        let (above_sum, above_carry) = full_add(above[0], above[1], above[2]);
+        let (below_sum, below_carry) = full_add(below[0], below[1], below[2]);
+        let (row_sum, row_carry) = (row[0] ^ row[2], row[0] & row[2]);
+        let (bit0, ones_carry) = full_add(above_sum, row_sum, below_sum);
+        let (twos, twos_carry) = full_add(above_carry, row_carry, below_carry);
+        let bit1 = twos ^ ones_carry;
+        let fours = twos & ones_carry;
+        let bit2 = twos_carry ^ fours;
+        let bit3 = twos_carry & fours;
+
+        let mut next = 0;
+        for n in 0..9 {
+            let rule = (self.birth[n] & !alive) | (self.survival[n] & alive);
+            if rule == 0 {
+                continue;
+            }
+            let select = |bit: u64, set: bool| if set { bit } else { !bit };
+            next |= rule
+                & select(bit0, n & 1 != 0)
+                & select(bit1, n & 2 != 0)
+                & select(bit2, n & 4 != 0)
+                & select(bit3, n & 8 != 0);
+        }
+        next
+    }
+}
+
+fn full_add(a: u64, b: u64, c: u64) -> (u64, u64) {
+    let partial = a ^ b;
+    (partial ^ c, (a & b) | (c & partial))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::Board;
+    use crate::testing::{self, PARALLEL_SIZES};
+
+    // Same soup on both boards, stepped side by side
+    fn compare(cols: usize, rows: usize, rule: Rule, topology: Topology, generations: u32) {
+        let mut naive = Board::new(cols, rows);
+        let mut packed = BitBoard::new(cols, rows);
+        for (i, alive) in testing::soup(cols, rows).into_iter().enumerate() {
+            naive.set(i % cols, i / cols, alive);
+            packed.set(i % cols, i / cols, alive);
+        }
+        naive.set_rule(rule);
+        naive.set_topology(topology);
+        packed.set_rule(rule);
+        packed.set_topology(topology);
+        for generation in 0..generations {
+            naive.step();
+            packed.step();
+            assert!(
+                naive.live_cells().eq(packed.live_cells()),
+                "{}x{} {} {:?} differs at generation {}",
+                cols,
+                rows,
+                rule,
+                topology,
+                generation + 1
+            );
+        }
+    }
+
+    #[test]
+    fn matches_naive_board() {
+        for topology in Topology::ALL {
+            for cols in [1, 2, 63, 64, 65, 130] {
+                for rows in [1, 3, 40] {
+                    compare(cols, rows, Rule::conway(), topology, 30);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn matches_naive_board_on_other_rules() {
+        for rule in ["B36/S23", "B2/S", "B3678/S34678", "B0/S8"] {
+            for topology in Topology::ALL {
+                compare(70, 20, rule.parse().unwrap(), topology, 20);
+            }
+        }
+    }
+
+    #[test]
+    fn parallel_matches_sequential() {
+        testing::with_pool(|| {
+            for topology in Topology::ALL {
+                for [cols, rows] in PARALLEL_SIZES {
+                    let mut sequential = BitBoard::new(cols, rows);
+                    let mut parallel = BitBoard::new(cols, rows);
+                    for (i, alive) in testing::soup(cols, rows).into_iter().enumerate() {
+                        sequential.set(i % cols, i / cols, alive);
+                        parallel.set(i % cols, i / cols, alive);
+                    }
+                    sequential.set_topology(topology);
+                    parallel.set_topology(topology);
+                    parallel.set_parallel(true);
+                    for _ in 0..40 {
+                        sequential.step();
+                        parallel.step();
+                        assert!(sequential.live_cells().eq(parallel.live_cells()));
+                    }
+                }
+            }
+        });
+    }
+
+    #[test]
+    fn resize_keeps_cells_relative_to_anchor() {
+        for anchor in [
+            Anchor::TopLeft,
+            Anchor::TopRight,
+            Anchor::BottomLeft,
+            Anchor::BottomRight,
+            Anchor::Centre,
+        ] {
+            let mut naive = Board::new(70, 9);
+            let mut packed = BitBoard::new(70, 9);
+            for (x, y) in [(0, 0), (69, 8), (35, 4), (66, 1)] {
+                naive.set(x, y, true);
+                packed.set(x, y, true);
+            }
+            for [cols, rows] in [[130, 20], [3, 2], [64, 9]] {
+                naive.resize(cols, rows, anchor);
+                packed.resize(cols, rows, anchor);
+                assert!(naive.live_cells().eq(packed.live_cells()));
+            }
+        }
+    }
+}
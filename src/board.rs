@@ -0,0 +1,422 @@
+use rayon::prelude::*;
+
+use crate::{rule::Rule, topology::Topology};
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Anchor {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+    Centre,
+}
+
+impl Anchor {
+    pub fn next(self) -> Self {
+        match self {
+            Anchor::TopLeft => Anchor::TopRight,
+            Anchor::TopRight => Anchor::BottomLeft,
+            Anchor::BottomLeft => Anchor::BottomRight,
+            Anchor::BottomRight => Anchor::Centre,
+            Anchor::Centre => Anchor::TopLeft,
+        }
+    }
+
+    // Where existing cells move to when a board grows by dx, dy (negative to shrink)
+    pub fn offset(self, dx: i64, dy: i64) -> [i64; 2] {
+        match self {
+            Anchor::TopLeft => [0, 0],
+            Anchor::TopRight => [dx, 0],
+            Anchor::BottomLeft => [0, dy],
+            Anchor::BottomRight => [dx, dy],
+            Anchor::Centre => [dx / 2, dy / 2],
+        }
+    }
+
+    // Where the given cells of a from sized board end up on a to sized one. Cells keep
+    // their position relative to the anchor, anything pushed off the edge is lost.
+    pub fn move_cells(
+        self,
+        cells: impl Iterator<Item = (usize, usize)>,
+        from: [usize; 2],
+        to: [usize; 2],
+    ) -> impl Iterator<Item = (usize, usize)> {
+        let [offset_x, offset_y] =
+            self.offset(to[0] as i64 - from[0] as i64, to[1] as i64 - from[1] as i64);
+        cells.filter_map(move |(x, y)| {
+            let nx = x as i64 + offset_x;
+            let ny = y as i64 + offset_y;
+            (nx >= 0 && nx < to[0] as i64 && ny >= 0 && ny < to[1] as i64)
+                .then_some((nx as usize, ny as usize))
+        })
+    }
+}
+
+// Side length of the square tiles used for activity tracking
+const TILE: usize = 16;
+
+// Fixed size board stored as a flat row-major array
+pub struct Board {
+    cols: usize,
+    rows: usize,
+    cells: Vec<bool>,
+    compute: Vec<bool>,
+    rule: Rule,
+    topology: Topology,
+    parallel: bool,
+    track_activity: bool,
+    // Tiles where a cell changed last generation or was edited since
+    active: Vec<bool>,
+}
+
+impl Board {
+    pub fn new(cols: usize, rows: usize) -> Self {
+        Self {
+            cols,
+            rows,
+            cells: vec![false; cols * rows],
+            compute: vec![false; cols * rows],
+            rule: Rule::default(),
+            topology: Topology::default(),
+            parallel: false,
+            track_activity: false,
+            active: vec![true; cols.div_ceil(TILE) * rows.div_ceil(TILE)],
+        }
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn rule(&self) -> Rule {
+        self.rule
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> bool {
+        self.cells[y * self.cols + x]
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, alive: bool) {
+        self.cells[y * self.cols + x] = alive;
+        let tile = (y / TILE) * self.cols.div_ceil(TILE) + x / TILE;
+        self.active[tile] = true;
+    }
+
+    pub fn live_cells(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.cells
+            .iter()
+            .enumerate()
+            .filter(|(_, &alive)| alive)
+            .map(|(i, _)| (i % self.cols, i / self.cols))
+    }
+
+    pub fn step(&mut self) {
+        if self.track_activity {
+            self.step_active();
+            return;
+        }
+        let mut compute = std::mem::take(&mut self.compute);
+        if self.parallel {
+            let band = band_rows(self.rows);
+            compute
+                .par_chunks_mut(band * self.cols)
+                .enumerate()
+                .for_each(|(i, out)| self.step_rows(i * band, out));
+        } else {
+            self.step_rows(0, &mut compute);
+        }
+        self.compute = std::mem::replace(&mut self.cells, compute);
+    }
+
+    // Computes the next state of the rows starting at first into out
+    fn step_rows(&self, first: usize, out: &mut [bool]) {
+        for (i, cell) in out.iter_mut().enumerate() {
+            let x = i % self.cols;
+            let y = first + i / self.cols;
+            let neighbours = count_neighbours(
+                &self.cells,
+                self.cols,
+                self.rows,
+                self.topology,
+                x as i64,
+                y as i64,
+            );
+            *cell = self.rule.next(self.get(x, y), neighbours);
+        }
+    }
+
+    // Only recomputes tiles that changed last generation and their neighbours, a cell
+    // whose neighbourhood didn't change can't change either
+    fn step_active(&mut self) {
+        let tiles_x = self.cols.div_ceil(TILE);
+        let tiles_y = self.rows.div_ceil(TILE);
+        let mut dirty = vec![false; self.active.len()];
+        let mut touches_edge = false;
+        for ty in 0..tiles_y {
+            for tx in 0..tiles_x {
+                if !self.active[ty * tiles_x + tx] {
+                    continue;
+                }
+                for ny in ty.saturating_sub(1)..(ty + 2).min(tiles_y) {
+                    for nx in tx.saturating_sub(1)..(tx + 2).min(tiles_x) {
+                        dirty[ny * tiles_x + nx] = true;
+                    }
+                }
+                touches_edge |= tx == 0 || ty == 0 || tx == tiles_x - 1 || ty == tiles_y - 1;
+            }
+        }
+        // Activity at one edge can reach any tile along the edges it's joined to
+        if touches_edge && self.topology != Topology::Bounded {
+            for ty in 0..tiles_y {
+                for tx in 0..tiles_x {
+                    if tx == 0 || ty == 0 || tx == tiles_x - 1 || ty == tiles_y - 1 {
+                        dirty[ty * tiles_x + tx] = true;
+                    }
+                }
+            }
+        }
+
+        let tiles: Vec<usize> = (0..dirty.len()).filter(|&t| dirty[t]).collect();
+        let next_tile = |&tile: &usize| (tile, self.step_tile(tile % tiles_x, tile / tiles_x));
+        let results: Vec<(usize, Vec<bool>)> = if self.parallel {
+            tiles.par_iter().map(next_tile).collect()
+        } else {
+            tiles.iter().map(next_tile).collect()
+        };
+
+        self.active.fill(false);
+        for (tile, next) in results {
+            let (tx, ty) = (tile % tiles_x, tile / tiles_x);
+            let width = TILE.min(self.cols - tx * TILE);
+            for (i, &alive) in next.iter().enumerate() {
+                let index = (ty * TILE + i / width) * self.cols + tx * TILE + i % width;
+                if self.cells[index] != alive {
+                    self.cells[index] = alive;
+                    self.active[tile] = true;
+                }
+            }
+        }
+    }
+
+    fn step_tile(&self, tx: usize, ty: usize) -> Vec<bool> {
+        let xs = tx * TILE..(tx * TILE + TILE).min(self.cols);
+        let ys = ty * TILE..(ty * TILE + TILE).min(self.rows);
+        let mut next = Vec::with_capacity(xs.len() * ys.len());
+        for y in ys {
+            for x in xs.clone() {
+                let neighbours = count_neighbours(
+                    &self.cells,
+                    self.cols,
+                    self.rows,
+                    self.topology,
+                    x as i64,
+                    y as i64,
+                );
+                next.push(self.rule.next(self.get(x, y), neighbours));
+            }
+        }
+        next
+    }
+
+    pub fn resize(&mut self, cols: usize, rows: usize, anchor: Anchor) {
+        let mut cells = vec![false; cols * rows];
+        let from = [self.cols, self.rows];
+        for (x, y) in anchor.move_cells(self.live_cells(), from, [cols, rows]) {
+            cells[y * cols + x] = true;
+        }
+        self.cols = cols;
+        self.rows = rows;
+        self.cells = cells;
+        self.compute = vec![false; cols * rows];
+        self.active = vec![true; cols.div_ceil(TILE) * rows.div_ceil(TILE)];
+    }
+
+    pub fn set_rule(&mut self, rule: Rule) {
+        self.rule = rule;
+        self.active.fill(true);
+    }
+
+    pub fn set_topology(&mut self, topology: Topology) {
+        self.topology = topology;
+        self.active.fill(true);
+    }
+
+    // Splits each step into row bands computed across the rayon thread pool
+    pub fn set_parallel(&mut self, parallel: bool) {
+        self.parallel = parallel;
+    }
+
+    // Skips tiles with no recent activity, giving the same result as a full scan
+    pub fn set_track_activity(&mut self, track_activity: bool) {
+        self.track_activity = track_activity;
+        // Nothing was recorded while tracking was off
+        self.active.fill(true);
+    }
+
+    // Number of active tiles out of the total
+    pub fn active_tiles(&self) -> (usize, usize) {
+        (
+            self.active.iter().filter(|&&a| a).count(),
+            self.active.len(),
+        )
+    }
+
+    pub fn population(&self) -> u64 {
+        self.cells.iter().filter(|&&alive| alive).count() as u64
+    }
+
+    pub fn clear(&mut self) {
+        self.cells.fill(false);
+        self.active.fill(true);
+    }
+}
+
+// Several bands per thread so uneven bands still balance out
+pub fn band_rows(rows: usize) -> usize {
+    rows.div_ceil(rayon::current_num_threads() * 4).max(1)
+}
+
+pub fn count_neighbours(
+    cells: &[bool],
+    cols: usize,
+    rows: usize,
+    topology: Topology,
+    cx: i64,
+    cy: i64,
+) -> u32 {
+    let mut count = 0;
+    for dx in -1i64..=1 {
+        for dy in -1i64..=1 {
+            if dx == 0 && dy == 0 {
+                continue;
+            }
+            if let Some((x, y)) = topology.wrap(cx + dx, cy + dy, cols, rows) {
+                if cells[y * cols + x] {
+                    count += 1;
+                }
+            }
+        }
+    }
+    count
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{self, PARALLEL_SIZES};
+
+    fn soup_board(cols: usize, rows: usize, topology: Topology) -> Board {
+        let mut board = Board::new(cols, rows);
+        board.set_topology(topology);
+        for (i, alive) in testing::soup(cols, rows).into_iter().enumerate() {
+            board.set(i % cols, i / cols, alive);
+        }
+        board
+    }
+
+    fn assert_same(expected: &Board, actual: &Board, what: &str, generation: u32) {
+        assert!(
+            expected.live_cells().eq(actual.live_cells()),
+            "{} {}x{} {:?} differs at generation {}",
+            what,
+            expected.cols,
+            expected.rows,
+            expected.topology,
+            generation
+        );
+    }
+
+    #[test]
+    fn parallel_matches_sequential() {
+        testing::with_pool(|| {
+            for topology in Topology::ALL {
+                for [cols, rows] in PARALLEL_SIZES {
+                    let mut sequential = soup_board(cols, rows, topology);
+                    let mut parallel = soup_board(cols, rows, topology);
+                    parallel.set_parallel(true);
+                    for generation in 1..=40 {
+                        sequential.step();
+                        parallel.step();
+                        assert_same(&sequential, &parallel, "parallel", generation);
+                    }
+                }
+            }
+        });
+    }
+
+    #[test]
+    fn activity_tracking_matches_full_scan() {
+        for topology in Topology::ALL {
+            for [cols, rows] in [[50, 50], [33, 17], [16, 48], [5, 70]] {
+                for parallel in [false, true] {
+                    let mut full = soup_board(cols, rows, topology);
+                    let mut tracked = soup_board(cols, rows, topology);
+                    tracked.set_track_activity(true);
+                    tracked.set_parallel(parallel);
+                    for generation in 1..=150 {
+                        full.step();
+                        tracked.step();
+                        assert_same(&full, &tracked, "tracked", generation);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn activity_tracking_follows_wrapped_edges() {
+        // A glider crossing every edge and corner of each surface
+        for topology in Topology::ALL {
+            let mut full = Board::new(40, 36);
+            full.set_topology(topology);
+            for (x, y) in [(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)] {
+                full.set(x + 30, y + 28, true);
+            }
+            let mut tracked = Board::new(40, 36);
+            tracked.set_topology(topology);
+            for (x, y) in full.live_cells().collect::<Vec<_>>() {
+                tracked.set(x, y, true);
+            }
+            tracked.set_track_activity(true);
+            for generation in 1..=400 {
+                full.step();
+                tracked.step();
+                assert_same(&full, &tracked, "glider", generation);
+            }
+        }
+    }
+
+    #[test]
+    fn activity_tracking_wakes_on_edit() {
+        let mut full = Board::new(64, 64);
+        let mut tracked = Board::new(64, 64);
+        tracked.set_track_activity(true);
+        // A block, which stays still, at a tile corner
+        for (x, y) in [(15, 15), (16, 15), (15, 16), (16, 16)] {
+            full.set(x, y, true);
+            tracked.set(x, y, true);
+        }
+        for generation in 1..=3 {
+            full.step();
+            tracked.step();
+            assert_same(&full, &tracked, "block", generation);
+        }
+        assert_eq!(tracked.active_tiles().0, 0);
+
+        // An R-pentomino beside it runs for over a thousand generations across tiles
+        for (x, y) in [(31, 30), (32, 30), (30, 31), (31, 31), (31, 32)] {
+            full.set(x, y, true);
+            tracked.set(x, y, true);
+        }
+        assert_eq!(tracked.active_tiles().0, 3);
+        for generation in 1..=200 {
+            full.step();
+            tracked.step();
+            assert_same(&full, &tracked, "edited", generation);
+        }
+    }
+}
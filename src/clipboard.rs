@@ -0,0 +1,114 @@
+use crate::pattern::{Format, ParseError, Pattern};
+
+// Text clipboard shared with other programs. Without a display server to hold
+// it (headless Linux, CI), or without the gui feature, text only round trips
+// within this process.
+pub struct Clipboard {
+    #[cfg(feature = "gui")]
+    system: Option<arboard::Clipboard>,
+    local: Option<String>,
+}
+
+impl Clipboard {
+    pub fn new() -> Self {
+        Self {
+            #[cfg(feature = "gui")]
+            system: arboard::Clipboard::new().ok(),
+            local: None,
+        }
+    }
+
+    // Never touches the system clipboard
+    pub fn local() -> Self {
+        Self {
+            #[cfg(feature = "gui")]
+            system: None,
+            local: None,
+        }
+    }
+
+    pub fn set_text(&mut self, text: String) {
+        #[cfg(feature = "gui")]
+        if let Some(system) = &mut self.system {
+            if system.set_text(text.clone()).is_ok() {
+                return;
+            }
+        }
+        self.local = Some(text);
+    }
+
+    pub fn text(&mut self) -> Option<String> {
+        #[cfg(feature = "gui")]
+        if let Some(text) = self
+            .system
+            .as_mut()
+            .and_then(|system| system.get_text().ok())
+        {
+            return Some(text);
+        }
+        self.local.clone()
+    }
+
+    // As RLE, like Golly, so other programs can paste it
+    pub fn set_pattern(&mut self, pattern: &Pattern) {
+        self.set_text(Format::Rle.write(pattern));
+    }
+
+    // None when the clipboard is empty, RLE is assumed for text in no known format
+    pub fn pattern(&mut self) -> Option<Result<Pattern, ParseError>> {
+        let text = self.text()?;
+        Some(Format::sniff(&text).unwrap_or(Format::Rle).read(&text))
+    }
+}
+
+impl Default for Clipboard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{engine, rule::Rule, sparse::SparseBoard};
+
+    #[test]
+    fn copies_and_pastes_a_selection() {
+        let mut board = SparseBoard::new();
+        board.set_rule("B36/S23".parse::<Rule>().unwrap());
+        // A glider inside the selection and a cell outside it
+        for (x, y) in [(11, 10), (12, 11), (10, 12), (11, 12), (12, 12), (30, 30)] {
+            board.set(x, y, true);
+        }
+        let mut clipboard = Clipboard::local();
+        clipboard.set_pattern(&engine::select(&board, [9, 9, 14, 13]));
+        assert!(clipboard.text().unwrap().contains("rule = B36/S23"));
+
+        let pattern = clipboard.pattern().unwrap().unwrap();
+        assert_eq!((pattern.width, pattern.height), (6, 5));
+        assert_eq!(pattern.rule, Some("B36/S23".parse().unwrap()));
+        let mut pasted = SparseBoard::new();
+        for &(x, y) in &pattern.cells {
+            pasted.set(x - 5, y + 20, true);
+        }
+        let mut cells: Vec<_> = pasted.live_cells().collect();
+        cells.sort_unstable();
+        assert_eq!(
+            cells,
+            vec![(-4, 23), (-3, 21), (-3, 23), (-2, 22), (-2, 23)]
+        );
+    }
+
+    #[test]
+    fn reads_other_formats_and_reports_errors() {
+        let mut clipboard = Clipboard::local();
+        assert!(clipboard.pattern().is_none());
+        clipboard.set_text(".O\nO.\n".to_string());
+        assert_eq!(
+            clipboard.pattern().unwrap().unwrap().cells,
+            vec![(1, 0), (0, 1)]
+        );
+        clipboard.set_text("hello".to_string());
+        assert!(clipboard.pattern().unwrap().is_err());
+    }
+}
@@ -0,0 +1,516 @@
+use crate::{
+    bitboard::BitBoard,
+    board::{Anchor, Board},
+    hashlife::HashLife,
+    pattern::{macrocell::Macrocell, Pattern},
+    rule::Rule,
+    soup::Soup,
+    sparse::SparseBoard,
+    topology::Topology,
+};
+
+// A cellular automaton addressed in i64 space. Bounded engines ignore cells
+// outside their board, so callers don't need to know which one is in use.
+pub trait Engine {
+    fn get(&self, x: i64, y: i64) -> bool;
+
+    fn set(&mut self, x: i64, y: i64, alive: bool);
+
+    fn step(&mut self);
+
+    fn rule(&self) -> Rule;
+
+    fn set_rule(&mut self, rule: Rule);
+
+    fn live_cells(&self) -> Vec<(i64, i64)>;
+
+    fn clear(&mut self);
+
+    fn toggle(&mut self, x: i64, y: i64) {
+        let alive = self.get(x, y);
+        self.set(x, y, !alive);
+    }
+
+    // Replaces the cols x rows rectangle with its top left at (x, y) with the soup,
+    // on bounded boards only the part inside the board is kept
+    fn randomize(&mut self, x: i64, y: i64, cols: usize, rows: usize, soup: &Soup) {
+        for (i, alive) in soup.cells(cols, rows).into_iter().enumerate() {
+            self.set(x + (i % cols) as i64, y + (i / cols) as i64, alive);
+        }
+    }
+
+    fn step_n(&mut self, generations: u64) {
+        for _ in 0..generations {
+            self.step();
+        }
+    }
+
+    fn population(&self) -> u64 {
+        self.live_cells().len() as u64
+    }
+
+    // Smallest and largest live coordinates, None when everything is dead
+    fn bounding_box(&self) -> Option<([i64; 2], [i64; 2])> {
+        let cells = self.live_cells();
+        let (&(x, y), rest) = cells.split_first()?;
+        Some(rest.iter().fold(([x, y], [x, y]), |(min, max), &(x, y)| {
+            (
+                [min[0].min(x), min[1].min(y)],
+                [max[0].max(x), max[1].max(y)],
+            )
+        }))
+    }
+
+    // Live cells in each block of a cols x rows grid of 2^level sided blocks with its
+    // top left at (x, y), row by row. Used to draw boards zoomed out past a cell a pixel.
+    fn density(&self, x: i64, y: i64, cols: usize, rows: usize, level: u32) -> Vec<u64> {
+        let mut counts = vec![0; cols * rows];
+        let block = 1i64 << level;
+        // Look up every cell of the area or go through the live ones, whichever is fewer
+        let area = cols as f64 * rows as f64 * (block * block) as f64;
+        if area <= self.population() as f64 {
+            for (i, count) in counts.iter_mut().enumerate() {
+                let bx = x + (i % cols) as i64 * block;
+                let by = y + (i / cols) as i64 * block;
+                for cy in by..by + block {
+                    for cx in bx..bx + block {
+                        *count += self.get(cx, cy) as u64;
+                    }
+                }
+            }
+        } else {
+            for (cx, cy) in self.live_cells() {
+                let bx = (cx - x).div_euclid(block);
+                let by = (cy - y).div_euclid(block);
+                if bx >= 0 && by >= 0 && bx < cols as i64 && by < rows as i64 {
+                    counts[by as usize * cols + bx as usize] += 1;
+                }
+            }
+        }
+        counts
+    }
+
+    // Board size for bounded engines, unbounded ones return None
+    fn size(&self) -> Option<[usize; 2]> {
+        None
+    }
+
+    fn resize(&mut self, _cols: usize, _rows: usize, _anchor: Anchor) {}
+
+    fn set_topology(&mut self, _topology: Topology) {}
+
+    fn set_parallel(&mut self, _parallel: bool) {}
+
+    fn set_track_activity(&mut self, _track_activity: bool) {}
+
+    // Active tiles out of the total, for engines that track activity
+    fn active_tiles(&self) -> Option<(usize, usize)> {
+        None
+    }
+
+    // Adds the macrocell's cells with its root centred on the origin
+    fn insert_macrocell(&mut self, macrocell: &Macrocell) {
+        let half = (1 << macrocell.level()) / 2;
+        for (x, y) in macrocell.live_cells() {
+            self.set(x - half, y - half, true);
+        }
+    }
+
+    fn to_macrocell(&self) -> Macrocell {
+        let mut pattern = Pattern::from_cells(self.live_cells());
+        pattern.rule = Some(self.rule());
+        Macrocell::from_pattern(&pattern)
+    }
+}
+
+// Copies the live cells of one engine into another, with (x, y) of the source
+// landing on (0, 0) of the target
+pub fn copy_cells(from: &dyn Engine, to: &mut dyn Engine, x: i64, y: i64) {
+    for (cx, cy) in from.live_cells() {
+        to.set(cx - x, cy - y, true);
+    }
+}
+
+impl Engine for Board {
+    fn get(&self, x: i64, y: i64) -> bool {
+        in_bounds(self.cols(), self.rows(), x, y).is_some_and(|(x, y)| Board::get(self, x, y))
+    }
+
+    fn set(&mut self, x: i64, y: i64, alive: bool) {
+        if let Some((x, y)) = in_bounds(self.cols(), self.rows(), x, y) {
+            Board::set(self, x, y, alive);
+        }
+    }
+
+    fn step(&mut self) {
+        Board::step(self);
+    }
+
+    fn rule(&self) -> Rule {
+        Board::rule(self)
+    }
+
+    fn set_rule(&mut self, rule: Rule) {
+        Board::set_rule(self, rule);
+    }
+
+    fn live_cells(&self) -> Vec<(i64, i64)> {
+        Board::live_cells(self)
+            .map(|(x, y)| (x as i64, y as i64))
+            .collect()
+    }
+
+    fn clear(&mut self) {
+        Board::clear(self);
+    }
+
+    fn population(&self) -> u64 {
+        Board::population(self)
+    }
+
+    fn density(&self, x: i64, y: i64, cols: usize, rows: usize, level: u32) -> Vec<u64> {
+        let [board_cols, board_rows] = [self.cols(), self.rows()];
+        window_density(board_cols, board_rows, x, y, cols, rows, level, |x, y| {
+            Board::get(self, x, y)
+        })
+    }
+
+    fn size(&self) -> Option<[usize; 2]> {
+        Some([self.cols(), self.rows()])
+    }
+
+    fn resize(&mut self, cols: usize, rows: usize, anchor: Anchor) {
+        Board::resize(self, cols, rows, anchor);
+    }
+
+    fn set_topology(&mut self, topology: Topology) {
+        Board::set_topology(self, topology);
+    }
+
+    fn set_parallel(&mut self, parallel: bool) {
+        Board::set_parallel(self, parallel);
+    }
+
+    fn set_track_activity(&mut self, track_activity: bool) {
+        Board::set_track_activity(self, track_activity);
+    }
+
+    fn active_tiles(&self) -> Option<(usize, usize)> {
+        Some(Board::active_tiles(self))
+    }
+}
+
+impl Engine for BitBoard {
+    fn get(&self, x: i64, y: i64) -> bool {
+        in_bounds(self.cols(), self.rows(), x, y).is_some_and(|(x, y)| BitBoard::get(self, x, y))
+    }
+
+    fn set(&mut self, x: i64, y: i64, alive: bool) {
+        if let Some((x, y)) = in_bounds(self.cols(), self.rows(), x, y) {
+            BitBoard::set(self, x, y, alive);
+        }
+    }
+
+    fn step(&mut self) {
+        BitBoard::step(self);
+    }
+
+    fn rule(&self) -> Rule {
+        BitBoard::rule(self)
+    }
+
+    fn set_rule(&mut self, rule: Rule) {
+        BitBoard::set_rule(self, rule);
+    }
+
+    fn live_cells(&self) -> Vec<(i64, i64)> {
+        BitBoard::live_cells(self)
+            .map(|(x, y)| (x as i64, y as i64))
+            .collect()
+    }
+
+    fn clear(&mut self) {
+        BitBoard::clear(self);
+    }
+
+    fn population(&self) -> u64 {
+        BitBoard::population(self)
+    }
+
+    fn density(&self, x: i64, y: i64, cols: usize, rows: usize, level: u32) -> Vec<u64> {
+        let [board_cols, board_rows] = [self.cols(), self.rows()];
+        window_density(board_cols, board_rows, x, y, cols, rows, level, |x, y| {
+            BitBoard::get(self, x, y)
+        })
+    }
+
+    fn size(&self) -> Option<[usize; 2]> {
+        Some([self.cols(), self.rows()])
+    }
+
+    fn resize(&mut self, cols: usize, rows: usize, anchor: Anchor) {
+        BitBoard::resize(self, cols, rows, anchor);
+    }
+
+    fn set_topology(&mut self, topology: Topology) {
+        BitBoard::set_topology(self, topology);
+    }
+
+    fn set_parallel(&mut self, parallel: bool) {
+        BitBoard::set_parallel(self, parallel);
+    }
+}
+
+impl Engine for SparseBoard {
+    fn get(&self, x: i64, y: i64) -> bool {
+        SparseBoard::get(self, x, y)
+    }
+
+    fn set(&mut self, x: i64, y: i64, alive: bool) {
+        SparseBoard::set(self, x, y, alive);
+    }
+
+    fn step(&mut self) {
+        SparseBoard::step(self);
+    }
+
+    fn rule(&self) -> Rule {
+        SparseBoard::rule(self)
+    }
+
+    fn set_rule(&mut self, rule: Rule) {
+        SparseBoard::set_rule(self, rule);
+    }
+
+    fn live_cells(&self) -> Vec<(i64, i64)> {
+        SparseBoard::live_cells(self).collect()
+    }
+
+    fn clear(&mut self) {
+        SparseBoard::clear(self);
+    }
+}
+
+impl Engine for HashLife {
+    fn get(&self, x: i64, y: i64) -> bool {
+        HashLife::get(self, x, y)
+    }
+
+    fn set(&mut self, x: i64, y: i64, alive: bool) {
+        HashLife::set(self, x, y, alive);
+    }
+
+    fn step(&mut self) {
+        self.set_step_log(0);
+        HashLife::step(self);
+    }
+
+    // One jump per set bit of the generation count, smallest first so each change of
+    // step size keeps the results of the smaller nodes. Bits past the largest jump
+    // are covered by repeating it
+    fn step_n(&mut self, generations: u64) {
+        let max = HashLife::MAX_STEP_LOG;
+        for step_log in (0..max).filter(|&k| generations & (1 << k) != 0) {
+            self.set_step_log(step_log);
+            HashLife::step(self);
+        }
+        if generations >> max > 0 {
+            self.set_step_log(max);
+            for _ in 0..generations >> max {
+                HashLife::step(self);
+            }
+        }
+    }
+
+    fn rule(&self) -> Rule {
+        HashLife::rule(self)
+    }
+
+    fn set_rule(&mut self, rule: Rule) {
+        HashLife::set_rule(self, rule);
+    }
+
+    fn live_cells(&self) -> Vec<(i64, i64)> {
+        HashLife::live_cells(self)
+    }
+
+    fn clear(&mut self) {
+        HashLife::clear(self);
+    }
+
+    fn population(&self) -> u64 {
+        HashLife::population(self)
+    }
+
+    fn bounding_box(&self) -> Option<([i64; 2], [i64; 2])> {
+        HashLife::bounding_box(self)
+    }
+
+    fn density(&self, x: i64, y: i64, cols: usize, rows: usize, level: u32) -> Vec<u64> {
+        HashLife::density(self, x, y, cols, rows, level)
+    }
+
+    fn insert_macrocell(&mut self, macrocell: &Macrocell) {
+        HashLife::insert_macrocell(self, macrocell);
+    }
+
+    fn to_macrocell(&self) -> Macrocell {
+        HashLife::to_macrocell(self)
+    }
+}
+
+// Live cells inside the inclusive rectangle [min_x, min_y, max_x, max_y], looking up
+// every cell of it or going through the live ones, whichever is fewer
+pub fn cells_in(engine: &dyn Engine, [min_x, min_y, max_x, max_y]: [i64; 4]) -> Vec<(i64, i64)> {
+    let area = (max_x - min_x + 1) as f64 * (max_y - min_y + 1) as f64;
+    if area <= engine.population() as f64 {
+        (min_y..=max_y)
+            .flat_map(|y| (min_x..=max_x).map(move |x| (x, y)))
+            .filter(|&(x, y)| engine.get(x, y))
+            .collect()
+    } else {
+        engine
+            .live_cells()
+            .into_iter()
+            .filter(|&(x, y)| x >= min_x && x <= max_x && y >= min_y && y <= max_y)
+            .collect()
+    }
+}
+
+// The cells inside the inclusive rectangle relative to its top left, with the engine's rule
+pub fn select(engine: &dyn Engine, selection: [i64; 4]) -> Pattern {
+    let [min_x, min_y, max_x, max_y] = selection;
+    Pattern {
+        width: (max_x - min_x + 1) as usize,
+        height: (max_y - min_y + 1) as usize,
+        cells: cells_in(engine, selection)
+            .into_iter()
+            .map(|(x, y)| (x - min_x, y - min_y))
+            .collect(),
+        rule: Some(engine.rule()),
+        ..Pattern::default()
+    }
+}
+
+// Engine::density for a bounded board, only looking at the part of the window on it
+#[allow(clippy::too_many_arguments)]
+fn window_density(
+    board_cols: usize,
+    board_rows: usize,
+    x: i64,
+    y: i64,
+    cols: usize,
+    rows: usize,
+    level: u32,
+    get: impl Fn(usize, usize) -> bool,
+) -> Vec<u64> {
+    let mut counts = vec![0; cols * rows];
+    let block = 1i64 << level;
+    let clamp = |from: i64, blocks: usize, size: usize| {
+        let to = from.saturating_add((blocks as i64).saturating_mul(block));
+        (from.clamp(0, size as i64), to.clamp(0, size as i64))
+    };
+    let (x0, x1) = clamp(x, cols, board_cols);
+    let (y0, y1) = clamp(y, rows, board_rows);
+    for cy in y0..y1 {
+        let row = ((cy - y) / block) as usize * cols;
+        for cx in x0..x1 {
+            if get(cx as usize, cy as usize) {
+                counts[row + ((cx - x) / block) as usize] += 1;
+            }
+        }
+    }
+    counts
+}
+
+fn in_bounds(cols: usize, rows: usize, x: i64, y: i64) -> Option<(usize, usize)> {
+    if x >= 0 && x < cols as i64 && y >= 0 && y < rows as i64 {
+        Some((x as usize, y as usize))
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+
+    // Density worked out from the live cells, what the trait default does on a sparse board
+    fn expected_density(
+        engine: &dyn Engine,
+        x: i64,
+        y: i64,
+        cols: usize,
+        rows: usize,
+        level: u32,
+    ) -> Vec<u64> {
+        let mut counts = vec![0; cols * rows];
+        for (cx, cy) in engine.live_cells() {
+            let bx = (cx - x).div_euclid(1 << level);
+            let by = (cy - y).div_euclid(1 << level);
+            if bx >= 0 && by >= 0 && bx < cols as i64 && by < rows as i64 {
+                counts[by as usize * cols + bx as usize] += 1;
+            }
+        }
+        counts
+    }
+
+    #[test]
+    fn bounded_engines_count_their_own_cells() {
+        let [cols, rows] = [70, 45];
+        let engines: [Box<dyn Engine>; 2] = [
+            Box::new(Board::new(cols, rows)),
+            Box::new(BitBoard::new(cols, rows)),
+        ];
+        for mut engine in engines {
+            for (i, alive) in testing::soup(cols, rows).into_iter().enumerate() {
+                engine.set((i % cols) as i64, (i / cols) as i64, alive);
+            }
+            engine.step();
+            assert_eq!(engine.population(), engine.live_cells().len() as u64);
+            // Windows inside, across the edges of and past the board
+            for (x, y, level) in [
+                (0, 0, 0),
+                (-5, -7, 1),
+                (60, 40, 2),
+                (-100, 3, 3),
+                (80, 0, 0),
+            ] {
+                assert_eq!(
+                    engine.density(x, y, 12, 9, level),
+                    expected_density(engine.as_ref(), x, y, 12, 9, level),
+                    "window at ({}, {}) level {}",
+                    x,
+                    y,
+                    level
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn select_looks_up_small_areas_and_filters_large_ones() {
+        let [cols, rows] = [40, 30];
+        let mut board = Board::new(cols, rows);
+        let mut sparse = SparseBoard::new();
+        for (i, alive) in testing::soup(cols, rows).into_iter().enumerate() {
+            Engine::set(&mut board, (i % cols) as i64, (i / cols) as i64, alive);
+            sparse.set((i % cols) as i64, (i / cols) as i64, alive);
+        }
+        // Small enough to look up cell by cell, then larger than the population
+        for selection in [[3, 4, 8, 6], [-10, -10, 50, 50]] {
+            let mut expected = select(&sparse, selection);
+            let mut pattern = select(&board, selection);
+            expected.cells.sort_unstable();
+            pattern.cells.sort_unstable();
+            assert_eq!(pattern, expected);
+        }
+        let pattern = select(&board, [3, 4, 8, 6]);
+        assert_eq!((pattern.width, pattern.height), (6, 3));
+        assert!(pattern
+            .cells
+            .iter()
+            .all(|&(x, y)| Engine::get(&board, x + 3, y + 4)));
+    }
+}
@@ -0,0 +1,3364 @@
+// The Piston window, everything gol-rs does apart from `run`
+use crate::ui::{Btn, HGroup, Widget};
+use gol_rs::backend::{parse_size, Backend, HASHLIFE_MEMORY_BUDGET};
+use gol_rs::board::Anchor;
+use gol_rs::clipboard::Clipboard;
+use gol_rs::engine::{self, copy_cells, Engine};
+use gol_rs::hashlife::HashLife;
+use gol_rs::history::{Change, History};
+use gol_rs::library::{self, Category};
+use gol_rs::pattern::{macrocell, Format, ParseError, Pattern};
+use gol_rs::rule::{Rule, RuleParseError};
+use gol_rs::shape::{self, Brush};
+use gol_rs::soup::{Soup, Symmetry};
+use gol_rs::topology::Topology;
+
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    path::Path,
+    str::FromStr,
+    time::SystemTime,
+};
+
+use glutin_window::GlutinWindow;
+use graphics::{ellipse::centered, types::FontSize, *};
+use opengl_graphics::{
+    CreateTexture, Filter, Format as TextureFormat, GlGraphics, GlyphCache, OpenGL, Texture,
+    TextureSettings, UpdateTexture,
+};
+use piston::{
+    Button, Event, EventSettings, Events, FileDrag, Input, Key, MouseButton, MouseCursorEvent,
+    MouseScrollEvent, PressEvent, ReleaseEvent, RenderArgs, RenderEvent, TextEvent, WindowSettings,
+};
+use rand::random;
+
+type Colour = [f32; 4];
+
+const WIDTH: u32 = 500;
+// Pattern library to the right of everything else
+const PANEL_WIDTH: u32 = 220;
+const HEIGHT: u32 = 750;
+
+const COLOUR_BACKGROUND: Colour = [0.09, 0.09, 0.09, 1.0];
+const COLOUR_ALIVE_CELL: Colour = [1.0; 4];
+const COLOUR_DEAD_CELL: Colour = COLOUR_BACKGROUND;
+const COLOUR_BUTTON: Colour = [1.0; 4];
+const COLOUR_HOVER: Colour = [0.8, 0.8, 0.8, 1.0];
+const COLOUR_REMOVE: Colour = [0.8, 0.0, 0.0, 1.0];
+const COLOUR_CURVE: Colour = [0.3, 0.3, 0.3, 1.0];
+const COLOUR_PREVIEW: Colour = [0.8, 0.8, 0.8, 0.5];
+const COLOUR_PREVIEW_REMOVE: Colour = [0.8, 0.0, 0.0, 0.5];
+const COLOUR_SELECTION: Colour = [0.2, 0.6, 1.0, 1.0];
+const COLOUR_SELECTION_FILL: Colour = [0.2, 0.6, 1.0, 0.15];
+
+const HISTORY_MEMORY_BUDGET: usize = 64 * 1024 * 1024;
+
+const MAX_BRUSH_SIZE: u32 = 32;
+const SOUP_DENSITY_STEP: u32 = 5;
+const SOUP_SIZES: [usize; 4] = [16, 32, 64, 128];
+// Largest area flood fill searches, a shape is drawn over or an AND or copy paste
+// clears, unbounded universes are only edited this way within the view
+const MAX_FILL_AREA: i64 = 1 << 22;
+
+const CAMERA_MIN_SCALE: f64 = 1.0 / 4096.0;
+const CAMERA_MAX_SCALE: f64 = 64.0;
+// Zoom per scroll step, and the share of the view an arrow key pans by
+const CAMERA_ZOOM_STEP: f64 = 1.25;
+const CAMERA_PAN_STEP: f64 = 0.1;
+const EXPORT_NAME: &str = "export";
+
+// Life, HighLife, Seeds, Day & Night, Life without Death, 2x2, Maze, Replicator
+const RULE_PRESETS: [&str; 8] = [
+    "B3/S23",
+    "B36/S23",
+    "B2/S",
+    "B3678/S34678",
+    "B3/S012345678",
+    "B36/S125",
+    "B3/S12345",
+    "B1357/S1357",
+];
+
+// Position and zoom of the grid view. Cell (x, y) is drawn as a square of side
+// scale at ((x - left) * scale, (y - top) * scale) from the grid's top left
+#[derive(Clone, Copy)]
+struct Camera {
+    left: f64,
+    top: f64,
+    // Pixels per cell, below 1 once cells are smaller than a pixel
+    scale: f64,
+}
+
+impl Camera {
+    // Centres the given cells in a view of width x height pixels
+    fn fit(cells: [f64; 4], width: f64, height: f64) -> Self {
+        let [x, y, cols, rows] = cells;
+        let scale = (width / cols)
+            .min(height / rows)
+            .clamp(CAMERA_MIN_SCALE, CAMERA_MAX_SCALE);
+        Self {
+            left: x - (width / scale - cols) / 2.0,
+            top: y - (height / scale - rows) / 2.0,
+            scale,
+        }
+    }
+
+    fn to_screen(self, x: f64, y: f64) -> [f64; 2] {
+        [(x - self.left) * self.scale, (y - self.top) * self.scale]
+    }
+
+    fn to_cell(self, [x, y]: [f64; 2]) -> [f64; 2] {
+        [self.left + x / self.scale, self.top + y / self.scale]
+    }
+
+    // The point under pos stays where it is
+    fn zoom_at(&mut self, factor: f64, pos: [f64; 2]) {
+        let [x, y] = self.to_cell(pos);
+        self.scale = (self.scale * factor).clamp(CAMERA_MIN_SCALE, CAMERA_MAX_SCALE);
+        self.left = x - pos[0] / self.scale;
+        self.top = y - pos[1] / self.scale;
+    }
+
+    fn pan(&mut self, dx: f64, dy: f64) {
+        self.left -= dx / self.scale;
+        self.top -= dy / self.scale;
+    }
+}
+
+// Colour of a texel where the given share of cells is alive
+fn shade(density: f32) -> [u8; 4] {
+    let mut rgba = [0; 4];
+    for (i, channel) in rgba.iter_mut().enumerate() {
+        let colour = COLOUR_DEAD_CELL[i] + (COLOUR_ALIVE_CELL[i] - COLOUR_DEAD_CELL[i]) * density;
+        *channel = (colour * 255.0).round() as u8;
+    }
+    rgba
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Tool {
+    Pen,
+    Line,
+    Rectangle,
+    FilledRectangle,
+    Ellipse,
+    FilledEllipse,
+    Fill,
+}
+
+impl Tool {
+    const ALL: [Tool; 7] = [
+        Tool::Pen,
+        Tool::Line,
+        Tool::Rectangle,
+        Tool::FilledRectangle,
+        Tool::Ellipse,
+        Tool::FilledEllipse,
+        Tool::Fill,
+    ];
+}
+
+// How a pasted pattern combines with the cells under its bounding box
+#[derive(Clone, Copy, PartialEq)]
+enum PasteMode {
+    Or,
+    And,
+    Xor,
+    Copy,
+}
+
+impl PasteMode {
+    fn next(self) -> Self {
+        match self {
+            PasteMode::Or => PasteMode::And,
+            PasteMode::And => PasteMode::Xor,
+            PasteMode::Xor => PasteMode::Copy,
+            PasteMode::Copy => PasteMode::Or,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            PasteMode::Or => "OR",
+            PasteMode::And => "AND",
+            PasteMode::Xor => "XOR",
+            PasteMode::Copy => "copy",
+        }
+    }
+
+    fn combine(self, alive: bool, pasted: bool) -> bool {
+        match self {
+            PasteMode::Or => alive || pasted,
+            PasteMode::And => alive && pasted,
+            PasteMode::Xor => alive != pasted,
+            PasteMode::Copy => pasted,
+        }
+    }
+}
+
+// [min_x, min_y, max_x, max_y] of the rectangle with the given opposite corners
+fn corners(from: [i64; 2], to: [i64; 2]) -> [i64; 4] {
+    [
+        from[0].min(to[0]),
+        from[1].min(to[1]),
+        from[0].max(to[0]),
+        from[1].max(to[1]),
+    ]
+}
+
+// A press and drag painting the grid, recorded as one edit on release. Shape
+// tools only draw on release, between start and the last cell.
+struct Stroke {
+    alive: bool,
+    start: [i64; 2],
+    last: [i64; 2],
+    // Live cells before the stroke, None when the history is skipped
+    before: Option<Vec<(i64, i64)>>,
+}
+
+struct Grid {
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+    // Visible window of the universe, which is the whole board when bounded
+    origin: [i64; 2],
+    cols: usize,
+    rows: usize,
+    universe: Box<dyn Engine>,
+    backend: Backend,
+    // Bytes HashLife's node cache may use before unreachable nodes are garbage-collected
+    hashlife_budget: usize,
+    hover: Option<[i64; 2]>,
+    camera: Camera,
+    // Last frame's cells, and whether it was blending blocks of them
+    texture: Option<(Texture, bool)>,
+    cursor: [f64; 2],
+    brush: Brush,
+    tool: Tool,
+    stroke: Option<Stroke>,
+    shift: bool,
+    // Inclusive [min_x, min_y, max_x, max_y], and the corner it's dragged from
+    selection: Option<[i64; 4]>,
+    selecting: Option<[i64; 2]>,
+    clipboard: Clipboard,
+    // Pattern taken from the clipboard, shown under the cursor while pasting
+    floating: Option<Pattern>,
+    pasting: bool,
+    paste_mode: PasteMode,
+    soup: Soup,
+    // Seed of the soup on the board, shown in the status line
+    last_seed: Option<u64>,
+    // Side of the centred square soups fill, None for the whole board or window
+    soup_size: Option<usize>,
+    // Last cursor position while dragging the view with the middle button
+    panning: Option<[f64; 2]>,
+    topology: Topology,
+    parallel: bool,
+    track_activity: bool,
+    anchor: Anchor,
+    // Result of the last load or save
+    message: Option<String>,
+    // Set while the board has too many cells for edits and steps to be undone
+    unrecorded: bool,
+    export_format: Format,
+    history: History,
+    generation: u64,
+}
+
+impl Grid {
+    fn new(x: u32, y: u32, width: u32, height: u32, cols: usize, rows: usize) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+            origin: [0, 0],
+            cols,
+            rows,
+            universe: Backend::Dense.create(cols, rows, HASHLIFE_MEMORY_BUDGET),
+            backend: Backend::Dense,
+            hashlife_budget: HASHLIFE_MEMORY_BUDGET,
+            hover: None,
+            camera: Camera::fit(
+                [0.0, 0.0, cols as f64, rows as f64],
+                width as f64,
+                height as f64,
+            ),
+            texture: None,
+            cursor: [0.0, 0.0],
+            brush: Brush::default(),
+            tool: Tool::Pen,
+            stroke: None,
+            shift: false,
+            selection: None,
+            selecting: None,
+            clipboard: Clipboard::new(),
+            floating: None,
+            pasting: false,
+            paste_mode: PasteMode::Or,
+            soup: Soup::default(),
+            last_seed: None,
+            soup_size: None,
+            panning: None,
+            topology: Topology::default(),
+            parallel: false,
+            track_activity: false,
+            anchor: Anchor::Centre,
+            message: None,
+            unrecorded: false,
+            export_format: Format::Rle,
+            history: History::new(HISTORY_MEMORY_BUDGET),
+            generation: 0,
+        }
+    }
+
+    fn render(&mut self, gl: &mut GlGraphics, args: &RenderArgs) {
+        let (x, y, width, height) = (
+            self.x as f64,
+            self.y as f64,
+            self.width as f64,
+            self.height as f64,
+        );
+        // Cells are clipped to the grid, the scissor works in framebuffer pixels
+        let ratio = args.draw_size[0] as f64 / args.window_size[0];
+        let clip = DrawState::new_alpha().scissor([
+            (x * ratio) as u32,
+            (y * ratio) as u32,
+            (width * ratio) as u32,
+            (height * ratio) as u32,
+        ]);
+        let camera = self.camera;
+        let size = camera.scale.max(1.0);
+
+        // The visible cells are uploaded as a texture with a texel per cell. Zoomed out
+        // past a cell a pixel, each texel averages a block of 2^level cells instead, so
+        // sparse areas come out as shades between the dead and alive colours.
+        let level = if camera.scale >= 1.0 {
+            0
+        } else {
+            (1.0 / camera.scale).log2().ceil() as u32
+        };
+        let block = 1i64 << level;
+        let texel = camera.scale * block as f64;
+        let [left, top] = camera.to_cell([0.0, 0.0]);
+        let bx = (left.floor() as i64).div_euclid(block) * block;
+        let by = (top.floor() as i64).div_euclid(block) * block;
+        let cols = (width / texel).ceil() as usize + 1;
+        let rows = (height / texel).ceil() as usize + 1;
+        let area = (block * block) as f32;
+        let pixels: Vec<u8> = self
+            .universe
+            .density(bx, by, cols, rows, level)
+            .into_iter()
+            .flat_map(|count| shade(count as f32 / area))
+            .collect();
+        let smooth = level > 0;
+        let texel_size = [cols as u32, rows as u32];
+        let texture = match self.texture.take() {
+            Some((mut texture, smoothed))
+                if smoothed == smooth && texture.get_size() == (cols as u32, rows as u32) =>
+            {
+                UpdateTexture::update(
+                    &mut texture,
+                    &mut (),
+                    TextureFormat::Rgba8,
+                    &pixels,
+                    [0, 0],
+                    texel_size,
+                )
+                .expect("Unable to update grid texture");
+                texture
+            }
+            _ => {
+                // Averaged blocks are blended, single cells keep hard edges
+                let filter = if smooth {
+                    Filter::Linear
+                } else {
+                    Filter::Nearest
+                };
+                Texture::create(
+                    &mut (),
+                    TextureFormat::Rgba8,
+                    &pixels,
+                    texel_size,
+                    &TextureSettings::new().filter(filter),
+                )
+                .expect("Unable to create grid texture")
+            }
+        };
+        // The shape being dragged out, drawn over the cells
+        let preview = self
+            .stroke
+            .as_ref()
+            .and_then(|stroke| Some((stroke.alive, self.shape_cells(stroke.start, stroke.last)?)));
+        let texture = &self.texture.insert((texture, smooth)).0;
+
+        gl.draw(args.viewport(), |c, g| {
+            let transform = c.transform.trans(x, y);
+            let [sx, sy] = camera.to_screen(bx as f64, by as f64);
+            Image::new()
+                .rect([sx, sy, cols as f64 * texel, rows as f64 * texel])
+                .draw(texture, &clip, transform, g);
+            if let Some([cols, rows]) = self.universe.size() {
+                let [sx, sy] = camera.to_screen(0.0, 0.0);
+                Rectangle::new_border(COLOUR_CURVE, 0.5).draw(
+                    [
+                        sx,
+                        sy,
+                        cols as f64 * camera.scale,
+                        rows as f64 * camera.scale,
+                    ],
+                    &clip,
+                    transform,
+                    g,
+                );
+            }
+            if let Some((alive, cells)) = &preview {
+                let colour = if *alive {
+                    COLOUR_PREVIEW
+                } else {
+                    COLOUR_PREVIEW_REMOVE
+                };
+                for &[cx, cy] in cells {
+                    let [sx, sy] = camera.to_screen(cx as f64, cy as f64);
+                    Rectangle::new(colour).draw([sx, sy, size, size], &clip, transform, g);
+                }
+            }
+            if let Some([min_x, min_y, max_x, max_y]) = self.selection {
+                let [sx, sy] = camera.to_screen(min_x as f64, min_y as f64);
+                let rect = [
+                    sx,
+                    sy,
+                    (max_x - min_x + 1) as f64 * camera.scale,
+                    (max_y - min_y + 1) as f64 * camera.scale,
+                ];
+                Rectangle::new(COLOUR_SELECTION_FILL).draw(rect, &clip, transform, g);
+                Rectangle::new_border(COLOUR_SELECTION, 1.0).draw(rect, &clip, transform, g);
+            }
+            if let (Some(pattern), Some([hx, hy]), true) =
+                (&self.floating, self.hover, self.pasting)
+            {
+                for &(cx, cy) in &pattern.cells {
+                    let [sx, sy] = camera.to_screen((hx + cx) as f64, (hy + cy) as f64);
+                    Rectangle::new(COLOUR_PREVIEW).draw([sx, sy, size, size], &clip, transform, g);
+                }
+                let [sx, sy] = camera.to_screen(hx as f64, hy as f64);
+                Rectangle::new_border(COLOUR_SELECTION, 1.0).draw(
+                    [
+                        sx,
+                        sy,
+                        pattern.width as f64 * camera.scale,
+                        pattern.height as f64 * camera.scale,
+                    ],
+                    &clip,
+                    transform,
+                    g,
+                );
+                return;
+            }
+            if let Some([hx, hy]) = self.hover {
+                // The brush outline, red when pressing would erase
+                let alive = match &self.stroke {
+                    Some(stroke) => stroke.alive,
+                    None => self.tool != Tool::Fill && !self.universe.get(hx, hy),
+                };
+                let colour = if alive { COLOUR_HOVER } else { COLOUR_REMOVE };
+                let footprint = match self.tool {
+                    Tool::Pen | Tool::Line | Tool::Rectangle | Tool::Ellipse => {
+                        self.brush.footprint()
+                    }
+                    Tool::FilledRectangle | Tool::FilledEllipse | Tool::Fill => vec![[0, 0]],
+                };
+                for [dx, dy] in footprint {
+                    let [sx, sy] = camera.to_screen((hx + dx) as f64, (hy + dy) as f64);
+                    Rectangle::new_border(colour, 1.0).draw(
+                        [sx, sy, size, size],
+                        &clip,
+                        transform,
+                        g,
+                    );
+                }
+            }
+        });
+    }
+
+    fn press(&mut self, button: Button, mouse_pos: [f64; 2]) {
+        if let Button::Keyboard(Key::LShift | Key::RShift) = button {
+            self.shift = true;
+        }
+
+        // Left draws, or erases when the pen starts on a live cell, right always erases.
+        // While pasting left places the pattern and right cancels, and shift selects.
+        if let Button::Mouse(mouse @ (MouseButton::Left | MouseButton::Right)) = button {
+            if let Some(cell) = self.cell_at(mouse_pos) {
+                if self.pasting {
+                    self.pasting = false;
+                    if mouse == MouseButton::Left {
+                        self.paste(cell);
+                    }
+                    return;
+                }
+                if self.shift && mouse == MouseButton::Left {
+                    self.selecting = Some(cell);
+                    self.selection = Some(corners(cell, cell));
+                    return;
+                }
+                let erase = match self.tool {
+                    Tool::Pen => self.universe.get(cell[0], cell[1]),
+                    _ => false,
+                };
+                let alive = mouse == MouseButton::Left && !erase;
+                match self.tool {
+                    Tool::Fill => self.fill(cell),
+                    Tool::Pen => {
+                        self.stroke = Some(Stroke {
+                            alive,
+                            start: cell,
+                            last: cell,
+                            before: self.snapshot(),
+                        });
+                        self.paint(cell, alive);
+                    }
+                    _ => {
+                        self.stroke = Some(Stroke {
+                            alive,
+                            start: cell,
+                            last: cell,
+                            before: None,
+                        })
+                    }
+                }
+            }
+        }
+
+        if let Button::Keyboard(key @ (Key::LeftBracket | Key::RightBracket)) = button {
+            self.brush.size = if key == Key::RightBracket {
+                (self.brush.size + 1).min(MAX_BRUSH_SIZE)
+            } else {
+                (self.brush.size - 1).max(1)
+            };
+            self.message = Some(format!("Brush {} {}", self.brush.size, self.brush.shape));
+        }
+
+        if let Button::Keyboard(Key::B) = button {
+            self.brush.shape = self.brush.shape.next();
+            self.message = Some(format!("Brush {} {}", self.brush.size, self.brush.shape));
+        }
+
+        match button {
+            Button::Keyboard(Key::Delete) => self.clear_selection(true),
+            Button::Keyboard(Key::Backspace) => self.clear_selection(false),
+            Button::Keyboard(Key::T) => self.transform(Pattern::rotate_clockwise),
+            Button::Keyboard(Key::H) => self.transform(Pattern::flip_horizontal),
+            Button::Keyboard(Key::U) => self.transform(Pattern::flip_vertical),
+            Button::Keyboard(Key::P) => {
+                self.paste_mode = self.paste_mode.next();
+                self.message = Some(format!("Paste mode {}", self.paste_mode.name()));
+            }
+            _ => {}
+        }
+
+        if let Button::Keyboard(Key::Space) = button {
+            self.calc_next();
+        }
+
+        // Shift repeats the last soup instead of picking a new seed
+        if let Button::Keyboard(Key::R) = button {
+            self.randomize(!self.shift);
+        }
+
+        if let Button::Keyboard(key @ (Key::Comma | Key::Period)) = button {
+            self.soup.density = if key == Key::Period {
+                (self.soup.density + SOUP_DENSITY_STEP).min(100)
+            } else {
+                self.soup
+                    .density
+                    .saturating_sub(SOUP_DENSITY_STEP)
+                    .max(SOUP_DENSITY_STEP)
+            };
+            self.message = Some(self.soup_settings());
+        }
+
+        if let Button::Keyboard(Key::M) = button {
+            self.soup.symmetry = self.soup.symmetry.next();
+            self.message = Some(self.soup_settings());
+        }
+
+        if let Button::Keyboard(Key::Q) = button {
+            let next = SOUP_SIZES
+                .iter()
+                .position(|&size| Some(size) == self.soup_size)
+                .map_or(0, |index| index + 1);
+            self.soup_size = SOUP_SIZES.get(next).copied();
+            self.message = Some(self.soup_settings());
+        }
+
+        if let Button::Keyboard(Key::Equals) = button {
+            self.resize(self.cols + 10, self.rows + 10, self.anchor);
+        }
+
+        if let Button::Keyboard(Key::Minus) = button {
+            self.resize(
+                self.cols.saturating_sub(10).max(10),
+                self.rows.saturating_sub(10).max(10),
+                self.anchor,
+            );
+        }
+
+        if let Button::Keyboard(Key::A) = button {
+            self.anchor = self.anchor.next();
+        }
+
+        if let Button::Keyboard(Key::S) = button {
+            let path = format!("{}.{}", EXPORT_NAME, self.export_format.extension());
+            self.save(Path::new(&path));
+        }
+
+        if let Button::Keyboard(Key::F) = button {
+            self.export_format = self.export_format.next();
+            self.message = Some(format!("Saving as {}", self.export_format));
+        }
+
+        if let Button::Mouse(MouseButton::Middle) = button {
+            if self.to_view(mouse_pos).is_some() {
+                self.panning = Some(mouse_pos);
+            }
+        }
+
+        let [pan_x, pan_y] = [
+            self.width as f64 * CAMERA_PAN_STEP,
+            self.height as f64 * CAMERA_PAN_STEP,
+        ];
+        match button {
+            Button::Keyboard(Key::Left) => self.camera.pan(pan_x, 0.0),
+            Button::Keyboard(Key::Right) => self.camera.pan(-pan_x, 0.0),
+            Button::Keyboard(Key::Up) => self.camera.pan(0.0, pan_y),
+            Button::Keyboard(Key::Down) => self.camera.pan(0.0, -pan_y),
+            Button::Keyboard(Key::V) => self.fit_pattern(),
+            Button::Keyboard(Key::Home) => self.reset_view(),
+            _ => {}
+        }
+        self.hover = self.cell_at(self.cursor);
+    }
+
+    fn release(&mut self, button: Button) {
+        match button {
+            Button::Mouse(MouseButton::Middle) => self.panning = None,
+            Button::Keyboard(Key::LShift | Key::RShift) => self.shift = false,
+            Button::Mouse(MouseButton::Left | MouseButton::Right) => {
+                // A shift click without dragging drops the selection
+                if let Some(start) = self.selecting.take() {
+                    if self.selection == Some(corners(start, start)) {
+                        self.selection = None;
+                    }
+                }
+                let Some(stroke) = self.stroke.take() else {
+                    return;
+                };
+                if self.tool == Tool::Pen {
+                    self.commit(Change::Edit, stroke.before);
+                } else {
+                    let Some(cells) = self.shape_cells(stroke.start, stroke.last) else {
+                        self.message = Some("Zoom in to draw".to_string());
+                        return;
+                    };
+                    self.record(Change::Edit, |grid| {
+                        for [x, y] in cells {
+                            grid.universe.set(x, y, stroke.alive);
+                        }
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn mouse_cursor(&mut self, pos: [f64; 2]) {
+        if let Some([last_x, last_y]) = self.panning {
+            self.camera.pan(pos[0] - last_x, pos[1] - last_y);
+            self.panning = Some(pos);
+        }
+        self.cursor = pos;
+        self.hover = self.cell_at(pos);
+        if let (Some(cell), Some(start)) = (self.hover, self.selecting) {
+            self.selection = Some(corners(start, cell));
+        }
+        // Fill in the cells between cursor events so fast strokes stay unbroken
+        if let (Some(cell), Some(stroke)) = (self.hover, &self.stroke) {
+            let (alive, last) = (stroke.alive, stroke.last);
+            if self.tool == Tool::Pen {
+                for point in shape::line(last, cell).into_iter().skip(1) {
+                    self.paint(point, alive);
+                }
+            }
+            if let Some(stroke) = &mut self.stroke {
+                stroke.last = cell;
+            }
+        }
+    }
+
+    // Cells the current shape tool covers between two corners, outlines drawn
+    // with the brush and clipped to the board or view. Empty for tools that aren't
+    // shapes, None for shapes too large to draw.
+    fn shape_cells(&self, from: [i64; 2], to: [i64; 2]) -> Option<Vec<[i64; 2]>> {
+        let [min_x, min_y, max_x, max_y] = corners(from, to);
+        if (max_x - min_x + 1).saturating_mul(max_y - min_y + 1) > MAX_FILL_AREA {
+            return None;
+        }
+        let [left, top, right, bottom] = self.edit_bounds();
+        let mut cells = self.unclipped_shape_cells(from, to);
+        cells.retain(|&[x, y]| x >= left && x <= right && y >= top && y <= bottom);
+        Some(cells)
+    }
+
+    fn unclipped_shape_cells(&self, from: [i64; 2], to: [i64; 2]) -> Vec<[i64; 2]> {
+        let outline = match self.tool {
+            Tool::Line => shape::line(from, to),
+            Tool::Rectangle => shape::rectangle(from, to, false),
+            Tool::Ellipse => shape::ellipse(from, to, false),
+            Tool::FilledRectangle => return shape::rectangle(from, to, true),
+            Tool::FilledEllipse => return shape::ellipse(from, to, true),
+            Tool::Pen | Tool::Fill => return Vec::new(),
+        };
+        let footprint = self.brush.footprint();
+        let mut cells: Vec<[i64; 2]> = outline
+            .iter()
+            .flat_map(|&[x, y]| footprint.iter().map(move |&[dx, dy]| [x + dx, y + dy]))
+            .collect();
+        cells.sort_unstable();
+        cells.dedup();
+        cells
+    }
+
+    // The board, or for unbounded universes the view, as an inclusive rectangle
+    fn edit_bounds(&self) -> [i64; 4] {
+        match self.universe.size() {
+            Some([cols, rows]) => [0, 0, cols as i64 - 1, rows as i64 - 1],
+            None => {
+                let [left, top] = self.camera.to_cell([0.0, 0.0]);
+                let [right, bottom] = self.camera.to_cell([self.width as f64, self.height as f64]);
+                [
+                    left.floor() as i64,
+                    top.floor() as i64,
+                    right.floor() as i64,
+                    bottom.floor() as i64,
+                ]
+            }
+        }
+    }
+
+    // Flips the region of same state cells around the given one, within the board
+    // or for unbounded universes the view
+    fn fill(&mut self, [x, y]: [i64; 2]) {
+        let bounds = self.edit_bounds();
+        let [min_x, min_y, max_x, max_y] = bounds;
+        if (max_x - min_x + 1).saturating_mul(max_y - min_y + 1) > MAX_FILL_AREA {
+            self.message = Some("Zoom in to fill".to_string());
+            return;
+        }
+        let alive = self.universe.get(x, y);
+        let region = shape::flood([x, y], bounds, |cx, cy| self.universe.get(cx, cy) == alive);
+        self.record(Change::Edit, |grid| {
+            for [cx, cy] in region {
+                grid.universe.set(cx, cy, !alive);
+            }
+        });
+    }
+
+    // Live cells inside the inclusive rectangle
+    // The selected cells relative to the selection's top left
+    fn selected_pattern(&self) -> Option<Pattern> {
+        Some(engine::select(self.universe.as_ref(), self.selection?))
+    }
+
+    fn copy(&mut self) {
+        let Some(pattern) = self.selected_pattern() else {
+            self.message = Some("Nothing selected".to_string());
+            return;
+        };
+        self.message = Some(format!("Copied {} cells", pattern.cells.len()));
+        self.clipboard.set_pattern(&pattern);
+    }
+
+    fn cut(&mut self) {
+        self.copy();
+        self.clear_selection(true);
+    }
+
+    // Floats a pattern under the cursor to be placed with a click, like a paste
+    fn stamp(&mut self, pattern: Pattern) {
+        self.message = Some(format!(
+            "Click to place {}",
+            pattern.name.as_deref().unwrap_or("the pattern")
+        ));
+        self.floating = Some(pattern);
+        self.pasting = true;
+    }
+
+    // Shows the pattern on the clipboard under the cursor until it's placed with a click
+    fn start_paste(&mut self) {
+        let Some(pattern) = self.clipboard.pattern() else {
+            self.message = Some("Clipboard is empty".to_string());
+            return;
+        };
+        match pattern {
+            Ok(pattern) => {
+                self.floating = Some(pattern);
+                self.pasting = true;
+                self.message = Some(format!("Pasting ({})", self.paste_mode.name()));
+            }
+            Err(error) => self.message = Some(format!("No pattern on the clipboard: {}", error)),
+        }
+    }
+
+    // Combines the floating pattern with the cells under its bounding box, top left at cell
+    fn paste(&mut self, [x, y]: [i64; 2]) {
+        let Some(pattern) = self.floating.clone() else {
+            return;
+        };
+        let mode = self.paste_mode;
+        // AND and copy also kill live cells where the pattern is dead, which are looked
+        // for in the part of its rectangle inside the board or view
+        let [left, top, right, bottom] = self.edit_bounds();
+        let [min_x, min_y] = [x.max(left), y.max(top)];
+        let max_x = x.saturating_add(pattern.width as i64 - 1).min(right);
+        let max_y = y.saturating_add(pattern.height as i64 - 1).min(bottom);
+        let clears = matches!(mode, PasteMode::And | PasteMode::Copy);
+        if clears && (max_x - min_x + 1).saturating_mul(max_y - min_y + 1) > MAX_FILL_AREA {
+            self.message = Some(format!("Zoom in to paste with {}", mode.name()));
+            return;
+        }
+        self.record(Change::Edit, |grid| {
+            if clears {
+                let pasted: HashSet<(i64, i64)> = pattern.cells.iter().copied().collect();
+                for cy in min_y..=max_y {
+                    for cx in min_x..=max_x {
+                        if !pasted.contains(&(cx - x, cy - y)) && grid.universe.get(cx, cy) {
+                            grid.universe.set(cx, cy, mode.combine(true, false));
+                        }
+                    }
+                }
+            }
+            for &(cx, cy) in &pattern.cells {
+                let alive = grid.universe.get(x + cx, y + cy);
+                grid.universe.set(x + cx, y + cy, mode.combine(alive, true));
+            }
+        });
+    }
+
+    // Kills every cell inside the selection, or everything outside it
+    fn clear_selection(&mut self, inside: bool) {
+        let Some(selection) = self.selection else {
+            self.message = Some("Nothing selected".to_string());
+            return;
+        };
+        let cells = engine::cells_in(self.universe.as_ref(), selection);
+        self.record(Change::Edit, |grid| {
+            if !inside {
+                grid.universe.clear();
+            }
+            for (x, y) in cells {
+                grid.universe.set(x, y, !inside);
+            }
+        });
+    }
+
+    // Rotates or flips the floating pattern while pasting, otherwise the selection in place
+    fn transform(&mut self, transform: fn(&Pattern) -> Pattern) {
+        if self.pasting {
+            self.floating = self.floating.as_ref().map(transform);
+            return;
+        }
+        let Some(pattern) = self.selected_pattern() else {
+            self.message = Some("Nothing selected".to_string());
+            return;
+        };
+        let Some([x, y, _, _]) = self.selection else {
+            return;
+        };
+        let transformed = transform(&pattern);
+        self.record(Change::Edit, |grid| {
+            for &(cx, cy) in &pattern.cells {
+                grid.universe.set(x + cx, y + cy, false);
+            }
+            for &(cx, cy) in &transformed.cells {
+                grid.universe.set(x + cx, y + cy, true);
+            }
+        });
+        self.selection = Some([
+            x,
+            y,
+            x + transformed.width as i64 - 1,
+            y + transformed.height as i64 - 1,
+        ]);
+    }
+
+    // Sets every cell under the brush centred on the given cell
+    fn paint(&mut self, [x, y]: [i64; 2], alive: bool) {
+        for [dx, dy] in self.brush.footprint() {
+            self.universe.set(x + dx, y + dy, alive);
+        }
+    }
+
+    // Zooms around the cell under the cursor
+    fn scroll(&mut self, scroll: [f64; 2]) {
+        if let Some(pos) = self.to_view(self.cursor) {
+            self.camera.zoom_at(CAMERA_ZOOM_STEP.powf(scroll[1]), pos);
+            self.hover = self.cell_at(self.cursor);
+        }
+    }
+
+    // Shows the whole board, or the window the board would have when unbounded
+    fn reset_view(&mut self) {
+        self.camera = Camera::fit(
+            [
+                self.origin[0] as f64,
+                self.origin[1] as f64,
+                self.cols as f64,
+                self.rows as f64,
+            ],
+            self.width as f64,
+            self.height as f64,
+        );
+    }
+
+    fn fit_pattern(&mut self) {
+        let Some(([min_x, min_y], [max_x, max_y])) = self.universe.bounding_box() else {
+            self.reset_view();
+            return;
+        };
+        // A cell of margin all round
+        self.camera = Camera::fit(
+            [
+                min_x as f64 - 1.0,
+                min_y as f64 - 1.0,
+                (max_x - min_x) as f64 + 3.0,
+                (max_y - min_y) as f64 + 3.0,
+            ],
+            self.width as f64,
+            self.height as f64,
+        );
+    }
+
+    // Position relative to the grid's top left, if it's over the grid
+    fn to_view(&self, pos: [f64; 2]) -> Option<[f64; 2]> {
+        let x = pos[0] - self.x as f64;
+        let y = pos[1] - self.y as f64;
+        (x > 0.0 && x < self.width as f64 && y > 0.0 && y < self.height as f64).then_some([x, y])
+    }
+
+    fn cell_at(&self, pos: [f64; 2]) -> Option<[i64; 2]> {
+        let [x, y] = self.camera.to_cell(self.to_view(pos)?);
+        let (x, y) = (x.floor() as i64, y.floor() as i64);
+        match self.universe.size() {
+            Some([cols, rows]) if x < 0 || y < 0 || x >= cols as i64 || y >= rows as i64 => None,
+            _ => Some([x, y]),
+        }
+    }
+
+    fn calc_next(&mut self) {
+        self.record(Change::Generations(1), |grid| grid.universe.step());
+        self.generation = self.generation.saturating_add(1);
+    }
+
+    fn jump(&mut self, step_log: u8) {
+        let generations = 1 << step_log.min(self.backend.max_step_log());
+        self.record(Change::Generations(generations), |grid| {
+            grid.universe.step_n(generations)
+        });
+        self.generation = self.generation.saturating_add(generations);
+    }
+
+    // Runs an operation on the engine and keeps what it changed in the history.
+    // Boards too full for a delta to fit the budget aren't diffed at all.
+    fn record<T>(&mut self, change: Change, operation: impl FnOnce(&mut Self) -> T) -> T {
+        let before = self.snapshot();
+        let result = operation(self);
+        self.commit(change, before);
+        result
+    }
+
+    // Sorted live cells to diff against once a change is done, None when there
+    // are too many for the history to hold. The history is cleared then, since
+    // undoing past the change would give the wrong board.
+    fn snapshot(&mut self) -> Option<Vec<(i64, i64)>> {
+        if self.universe.population() as usize > self.history.max_cells() {
+            self.history.clear();
+            self.unrecorded = true;
+            return None;
+        }
+        let mut cells = self.universe.live_cells();
+        cells.sort_unstable();
+        Some(cells)
+    }
+
+    fn commit(&mut self, change: Change, before: Option<Vec<(i64, i64)>>) {
+        let Some(before) = before else {
+            return;
+        };
+        if let Some(after) = self.snapshot() {
+            self.history.record(change, &before, &after);
+            self.unrecorded = false;
+        }
+    }
+
+    fn undo(&mut self) {
+        match self.history.undo(self.universe.as_mut()) {
+            Some(Change::Generations(generations)) => {
+                self.generation = self.generation.saturating_sub(generations)
+            }
+            Some(Change::Edit) => {}
+            None => self.message = Some("Nothing to undo".to_string()),
+        }
+    }
+
+    fn redo(&mut self) {
+        match self.history.redo(self.universe.as_mut()) {
+            Some(Change::Generations(generations)) => {
+                self.generation = self.generation.saturating_add(generations)
+            }
+            Some(Change::Edit) => {}
+            None => self.message = Some("Nothing to redo".to_string()),
+        }
+    }
+
+    // Undoes or redoes until the history is at the given position
+    fn seek(&mut self, position: usize) {
+        while self.history.position() > position {
+            self.undo();
+        }
+        while self.history.position() < position && self.history.redoable() {
+            self.redo();
+        }
+    }
+
+    // Only rewinds generations, edits made since have to be undone first
+    fn step_back(&mut self) {
+        if let Some(Change::Generations(_)) = self.history.last() {
+            self.undo();
+        } else {
+            self.message = Some("No earlier generation recorded".to_string());
+        }
+    }
+
+    // A bounded board is resized, an unbounded universe just shows a different window
+    fn resize(&mut self, cols: usize, rows: usize, anchor: Anchor) {
+        let dx = cols as i64 - self.cols as i64;
+        let dy = rows as i64 - self.rows as i64;
+        if self.universe.size().is_some() {
+            self.universe.resize(cols, rows, anchor);
+            // Cells have moved, so the recorded deltas no longer line up
+            self.history.clear();
+        } else {
+            let [offset_x, offset_y] = anchor.offset(dx, dy);
+            self.origin[0] -= offset_x;
+            self.origin[1] -= offset_y;
+        }
+        self.cols = cols;
+        self.rows = rows;
+        self.hover = None;
+        self.reset_view();
+    }
+
+    // Unbounded engines never apply B0, so they would quietly run a different rule
+    fn set_rule(&mut self, rule: Rule) {
+        if rule.birth(0) && self.universe.size().is_none() {
+            self.message = Some(format!("{} needs a bounded backend", rule));
+            return;
+        }
+        self.universe.set_rule(rule);
+    }
+
+    fn set_topology(&mut self, topology: Topology) {
+        self.topology = topology;
+        self.universe.set_topology(topology);
+    }
+
+    fn set_parallel(&mut self, parallel: bool) {
+        self.parallel = parallel;
+        self.universe.set_parallel(parallel);
+    }
+
+    fn set_track_activity(&mut self, track_activity: bool) {
+        self.track_activity = track_activity;
+        self.universe.set_track_activity(track_activity);
+    }
+
+    fn status(&self) -> String {
+        let tiles = match self.universe.active_tiles() {
+            Some((active, total)) if self.track_activity => {
+                Some(format!("Active tiles {}/{}", active, total))
+            }
+            _ => None,
+        };
+        let generation = Some(format!("Gen {}", self.generation));
+        let seed = self.last_seed.map(|seed| format!("Seed {}", seed));
+        let unrecorded = self.unrecorded.then(|| {
+            format!(
+                "No undo over {} cells, history cleared",
+                self.history.max_cells()
+            )
+        });
+        [generation, tiles, seed, unrecorded, self.message.clone()]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>()
+            .join("   ")
+    }
+
+    // Stamps the pattern with its top left corner at the given cell
+    fn place(&mut self, pattern: &Pattern, [x, y]: [i64; 2]) {
+        for &(cx, cy) in &pattern.cells {
+            self.universe.set(x + cx, y + cy, true);
+        }
+        if let Some(rule) = pattern.rule {
+            self.set_rule(rule);
+        }
+    }
+
+    // Under the cursor if there is one, otherwise centred in the view
+    fn placement(&self, pattern: &Pattern) -> [i64; 2] {
+        let [x, y] = self
+            .camera
+            .to_cell([self.width as f64 / 2.0, self.height as f64 / 2.0]);
+        self.hover.unwrap_or([
+            x as i64 - pattern.width as i64 / 2,
+            y as i64 - pattern.height as i64 / 2,
+        ])
+    }
+
+    fn to_pattern(&self) -> Pattern {
+        let mut pattern = Pattern::from_cells(self.universe.live_cells());
+        pattern.rule = Some(self.universe.rule());
+        pattern
+    }
+
+    fn load(&mut self, path: &Path) -> Option<Rule> {
+        let result = fs::read_to_string(path)
+            .map_err(|error| error.to_string())
+            .and_then(|text| {
+                self.record(Change::Edit, |grid| grid.load_text(path, &text))
+                    .map_err(|error| error.to_string())
+            });
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        match result {
+            Ok(rule) => {
+                self.message = Some(format!("Loaded {}", name));
+                rule
+            }
+            Err(error) => {
+                eprintln!("Unable to load {}: {}", path.display(), error);
+                self.message = Some(format!("{}: {}", name, error));
+                None
+            }
+        }
+    }
+
+    // Macrocells go straight into an unbounded engine (as a tree for HashLife),
+    // everything else is placed cell by cell
+    fn load_text(&mut self, path: &Path, text: &str) -> Result<Option<Rule>, ParseError> {
+        let format = Format::detect(path, text);
+        if format == Format::Macrocell && self.universe.size().is_none() {
+            let macrocell = macrocell::read(text)?;
+            self.universe.insert_macrocell(&macrocell);
+            if let Some(rule) = macrocell.rule {
+                self.set_rule(rule);
+            }
+            // The tree is centred on the origin, so the view follows it
+            self.origin = [-(self.cols as i64) / 2, -(self.rows as i64) / 2];
+            self.fit_pattern();
+            return Ok(macrocell.rule);
+        }
+        let pattern = format.read(text)?;
+        let offset = self.placement(&pattern);
+        self.place(&pattern, offset);
+        Ok(pattern.rule)
+    }
+
+    fn save(&mut self, path: &Path) {
+        let text = match self.export_format {
+            Format::Macrocell => macrocell::write(&self.universe.to_macrocell()),
+            format => format.write(&self.to_pattern()),
+        };
+        match fs::write(path, text) {
+            Ok(()) => self.message = Some(format!("Saved {}", path.display())),
+            Err(error) => {
+                eprintln!("Unable to save {}: {}", path.display(), error);
+                self.message = Some(format!("Unable to save {}", path.display()));
+            }
+        }
+    }
+
+    // The visible window becomes the board when switching to a bounded backend
+    fn set_backend(&mut self, backend: Backend) {
+        if backend == self.backend {
+            return;
+        }
+        let mut engine = backend.create(self.cols, self.rows, self.hashlife_budget);
+        let rule = self.universe.rule();
+        if rule.birth(0) && engine.size().is_none() {
+            self.message = Some(format!("{} needs a bounded backend, using B3/S23", rule));
+        } else {
+            engine.set_rule(rule);
+        }
+        engine.set_topology(self.topology);
+        engine.set_parallel(self.parallel);
+        engine.set_track_activity(self.track_activity);
+        if engine.size().is_some() {
+            let [x, y] = self.origin;
+            copy_cells(self.universe.as_ref(), engine.as_mut(), x, y);
+            self.origin = [0, 0];
+        } else {
+            copy_cells(self.universe.as_ref(), engine.as_mut(), 0, 0);
+        }
+        self.universe = engine;
+        self.backend = backend;
+        self.history.clear();
+        self.hover = None;
+        self.reset_view();
+    }
+
+    // Fills the selection, the centred square if a size is set, or otherwise the
+    // whole board or window with a soup. A new seed is picked unless repeating the
+    // last soup.
+    fn randomize(&mut self, new_seed: bool) {
+        if new_seed {
+            self.soup.seed = random::<u32>() as u64;
+        }
+        let [x, y, cols, rows] = match (self.selection, self.soup_size) {
+            (Some([min_x, min_y, max_x, max_y]), _) => {
+                [min_x, min_y, max_x - min_x + 1, max_y - min_y + 1]
+            }
+            (None, Some(size)) => [
+                self.origin[0] + (self.cols as i64 - size as i64) / 2,
+                self.origin[1] + (self.rows as i64 - size as i64) / 2,
+                size as i64,
+                size as i64,
+            ],
+            (None, None) => [
+                self.origin[0],
+                self.origin[1],
+                self.cols as i64,
+                self.rows as i64,
+            ],
+        };
+        let soup = self.soup;
+        self.last_seed = Some(soup.seed);
+        self.record(Change::Edit, |grid| {
+            grid.universe
+                .randomize(x, y, cols as usize, rows as usize, &soup)
+        });
+        self.message = Some(self.soup_settings());
+    }
+
+    fn soup_settings(&self) -> String {
+        let area = match self.soup_size {
+            Some(size) => format!(", {}x{} centred", size, size),
+            None => String::new(),
+        };
+        let symmetry = match self.soup.symmetry {
+            Symmetry::None => String::new(),
+            symmetry => format!(", {} symmetry", symmetry),
+        };
+        format!("Soup density {}%{}{}", self.soup.density, symmetry, area)
+    }
+
+    // Puts the seed on the clipboard, so a soup can be shared and made again
+    fn copy_seed(&mut self) {
+        let Some(seed) = self.last_seed else {
+            self.message = Some("No soup to copy the seed of".to_string());
+            return;
+        };
+        self.clipboard.set_text(seed.to_string());
+        self.message = Some(format!("Copied seed {}", seed));
+    }
+}
+
+struct StepBack {
+    x: f64,
+    y: f64,
+    width: f64,
+    height: f64,
+    hover: bool,
+}
+
+impl Btn for StepBack {
+    fn new(x: u32, y: u32, width: u32, height: u32) -> Self {
+        Self {
+            x: x as f64,
+            y: y as f64,
+            width: width as f64,
+            height: height as f64,
+            hover: false,
+        }
+    }
+
+    fn render(&self, gl: &mut GlGraphics, args: &RenderArgs) {
+        gl.draw(args.viewport(), |c, g| {
+            let colour = if self.hover {
+                COLOUR_HOVER
+            } else {
+                COLOUR_BUTTON
+            };
+            // Next, mirrored
+            let pad = self.width / 5.0;
+            Polygon::new(colour).draw(
+                &[
+                    [self.x + self.width - pad, self.y + pad],
+                    [self.x + self.width - pad, self.y + self.height - pad],
+                    [self.x + pad, self.y + (self.height / 2.0)],
+                ],
+                &DrawState::new_alpha(),
+                c.transform,
+                g,
+            );
+            let bar_width = self.width / 10.0;
+            let bar_height = self.height - 2.0 * pad;
+            Rectangle::new(colour).draw(
+                [self.x + pad, self.y + pad, bar_width, bar_height],
+                &DrawState::new_alpha(),
+                c.transform,
+                g,
+            );
+        })
+    }
+
+    fn mouse_cursor(&mut self, pos: [f64; 2]) {
+        self.hover = pos[0] > self.x
+            && pos[0] < self.x + self.width
+            && pos[1] > self.y
+            && pos[1] < self.y + self.height;
+    }
+
+    fn is_pressed(&mut self, button: &Button) -> bool {
+        Button::Mouse(MouseButton::Left) == *button && self.hover
+    }
+}
+
+impl Widget for StepBack {
+    fn pos(&self) -> [f64; 2] {
+        [self.x, self.y]
+    }
+
+    fn size(&self) -> [f64; 2] {
+        [self.width, self.height]
+    }
+
+    fn set_pos(&mut self, x: f64, y: f64) {
+        self.x = x;
+        self.y = y;
+    }
+
+    fn set_size(&mut self, width: f64, height: f64) {
+        self.width = width;
+        self.height = height;
+    }
+}
+
+struct Next {
+    x: f64,
+    y: f64,
+    width: f64,
+    height: f64,
+    hover: bool,
+}
+
+impl Btn for Next {
+    fn new(x: u32, y: u32, width: u32, height: u32) -> Self {
+        Self {
+            x: x as f64,
+            y: y as f64,
+            width: width as f64,
+            height: height as f64,
+            hover: false,
+        }
+    }
+
+    fn render(&self, gl: &mut GlGraphics, args: &RenderArgs) {
+        gl.draw(args.viewport(), |c, g| {
+            let colour = if self.hover {
+                COLOUR_HOVER
+            } else {
+                COLOUR_BUTTON
+            };
+            let pad = self.width / 5.0;
+            Polygon::new(colour).draw(
+                &[
+                    [self.x + pad, self.y + pad],
+                    [self.x + pad, self.y + self.height - pad],
+                    [self.x + self.width - pad, self.y + (self.height / 2.0)],
+                ],
+                &DrawState::new_alpha(),
+                c.transform,
+                g,
+            );
+            let bar_width = self.width / 10.0;
+            let bar_height = self.height - 2.0 * pad;
+            Rectangle::new(colour).draw(
+                [
+                    self.x + self.width - pad - bar_width,
+                    self.y + pad,
+                    bar_width,
+                    bar_height,
+                ],
+                &DrawState::new_alpha(),
+                c.transform,
+                g,
+            );
+        })
+    }
+
+    fn mouse_cursor(&mut self, pos: [f64; 2]) {
+        self.hover = pos[0] > self.x
+            && pos[0] < self.x + self.width
+            && pos[1] > self.y
+            && pos[1] < self.y + self.height;
+    }
+
+    fn is_pressed(&mut self, button: &Button) -> bool {
+        Button::Mouse(MouseButton::Left) == *button && self.hover
+    }
+}
+
+impl Widget for Next {
+    fn pos(&self) -> [f64; 2] {
+        [self.x, self.y]
+    }
+
+    fn size(&self) -> [f64; 2] {
+        [self.width, self.height]
+    }
+
+    fn set_pos(&mut self, x: f64, y: f64) {
+        self.x = x;
+        self.y = y;
+    }
+
+    fn set_size(&mut self, width: f64, height: f64) {
+        self.width = width;
+        self.height = height;
+    }
+}
+
+struct Jump {
+    x: f64,
+    y: f64,
+    width: f64,
+    height: f64,
+    hover: bool,
+    step_log: u8,
+    min: u8,
+    max: u8,
+}
+
+impl Jump {
+    fn new(x: u32, y: u32, width: u32, height: u32, step_log: u8, min: u8, max: u8) -> Self {
+        Self {
+            x: x as f64,
+            y: y as f64,
+            width: width as f64,
+            height: height as f64,
+            hover: false,
+            step_log,
+            min,
+            max,
+        }
+    }
+
+    // Drawn as 2^k
+    fn render(&self, gl: &mut GlGraphics, args: &RenderArgs, glyph_cache: &mut GlyphCache) {
+        let colour = if self.hover {
+            COLOUR_HOVER
+        } else {
+            COLOUR_BUTTON
+        };
+        let base_size = 26;
+        let exponent_size = 14;
+        let exponent = self.step_log.to_string();
+        let base_width = glyph_cache
+            .width(base_size, "2")
+            .expect("Unable to measure text");
+        let exponent_width = glyph_cache
+            .width(exponent_size, &exponent)
+            .expect("Unable to measure text");
+        let x = self.x + self.width / 2.0 - (base_width + exponent_width) / 2.0;
+        let y = self.y + self.height / 2.0 + base_size as f64 / 2.0 - 5.0;
+        gl.draw(args.viewport(), |c, g| {
+            Text::new_color(colour, base_size)
+                .draw(
+                    "2",
+                    glyph_cache,
+                    &DrawState::default(),
+                    c.transform.trans(x, y),
+                    g,
+                )
+                .expect("Unable to draw text");
+            Text::new_color(colour, exponent_size)
+                .draw(
+                    &exponent,
+                    glyph_cache,
+                    &DrawState::default(),
+                    c.transform
+                        .trans(x + base_width, y - base_size as f64 / 2.0),
+                    g,
+                )
+                .expect("Unable to draw text");
+        });
+    }
+
+    fn mouse_cursor(&mut self, pos: [f64; 2]) {
+        self.hover = pos[0] > self.x
+            && pos[0] < self.x + self.width
+            && pos[1] > self.y
+            && pos[1] < self.y + self.height;
+    }
+
+    fn is_pressed(&mut self, button: &Button) -> bool {
+        *button == Button::Mouse(MouseButton::Left) && self.hover
+    }
+
+    // Lowers the largest jump, bringing the current one down with it
+    fn set_max(&mut self, max: u8) {
+        self.max = max;
+        self.step_log = self.step_log.min(max);
+    }
+
+    // Scrolling over the button changes the size of the jump
+    fn scroll(&mut self, scroll: [f64; 2]) {
+        if !self.hover {
+            return;
+        }
+        if scroll[1] > 0.0 && self.step_log < self.max {
+            self.step_log += 1;
+        } else if scroll[1] < 0.0 && self.step_log > self.min {
+            self.step_log -= 1;
+        }
+    }
+}
+
+impl Widget for Jump {
+    fn pos(&self) -> [f64; 2] {
+        [self.x, self.y]
+    }
+
+    fn size(&self) -> [f64; 2] {
+        [self.width, self.height]
+    }
+
+    fn set_pos(&mut self, x: f64, y: f64) {
+        self.x = x;
+        self.y = y;
+    }
+
+    fn set_size(&mut self, width: f64, height: f64) {
+        self.width = width;
+        self.height = height;
+    }
+}
+
+struct Play {
+    x: f64,
+    y: f64,
+    width: f64,
+    height: f64,
+    hover: bool,
+    toggle: bool,
+}
+
+impl Btn for Play {
+    fn new(x: u32, y: u32, width: u32, height: u32) -> Self {
+        Self {
+            x: x as f64,
+            y: y as f64,
+            width: width as f64,
+            height: height as f64,
+            hover: false,
+            toggle: false,
+        }
+    }
+
+    fn render(&self, gl: &mut GlGraphics, args: &RenderArgs) {
+        let colour = if self.hover {
+            COLOUR_HOVER
+        } else {
+            COLOUR_BUTTON
+        };
+        let pad = self.width / 5.0;
+        if self.toggle {
+            gl.draw(args.viewport(), |c, g| {
+                Rectangle::new(colour).draw(
+                    [
+                        self.x + pad,
+                        self.y + pad,
+                        self.width / 5.0,
+                        self.height - pad * 2.0,
+                    ],
+                    &DrawState::new_alpha(),
+                    c.transform,
+                    g,
+                );
+                Rectangle::new(colour).draw(
+                    [
+                        self.x + self.width - pad - (self.width / 5.0),
+                        self.y + pad,
+                        self.width / 5.0,
+                        self.height - pad * 2.0,
+                    ],
+                    &DrawState::new_alpha(),
+                    c.transform,
+                    g,
+                );
+            })
+        } else {
+            gl.draw(args.viewport(), |c, g| {
+                Polygon::new(colour).draw(
+                    &[
+                        [self.x + pad, self.y + pad],
+                        [self.x + pad, self.y + self.height - pad],
+                        [self.x + self.width - pad, self.y + (self.height / 2.0)],
+                    ],
+                    &DrawState::new_alpha(),
+                    c.transform,
+                    g,
+                );
+            })
+        }
+    }
+
+    fn mouse_cursor(&mut self, pos: [f64; 2]) {
+        self.hover = pos[0] > self.x
+            && pos[0] < self.x + self.width
+            && pos[1] > self.y
+            && pos[1] < self.y + self.height;
+    }
+
+    fn is_pressed(&mut self, button: &Button) -> bool {
+        if *button == Button::Mouse(MouseButton::Left) && self.hover {
+            self.toggle = !self.toggle;
+            return true;
+        }
+        false
+    }
+}
+
+impl Widget for Play {
+    fn pos(&self) -> [f64; 2] {
+        [self.x, self.y]
+    }
+
+    fn size(&self) -> [f64; 2] {
+        [self.width, self.height]
+    }
+
+    fn set_pos(&mut self, x: f64, y: f64) {
+        self.x = x;
+        self.y = y;
+    }
+
+    fn set_size(&mut self, width: f64, height: f64) {
+        self.width = width;
+        self.height = height;
+    }
+}
+
+#[derive(PartialEq)]
+enum Dice {
+    One,
+    Two,
+    Three,
+    Four,
+    Five,
+    Six,
+}
+
+// The face shows the soup symmetry, one dot for none up to six for D8
+impl From<Symmetry> for Dice {
+    fn from(symmetry: Symmetry) -> Self {
+        match symmetry {
+            Symmetry::None => Dice::One,
+            Symmetry::C2 => Dice::Two,
+            Symmetry::D2 => Dice::Three,
+            Symmetry::C4 => Dice::Four,
+            Symmetry::D4 => Dice::Five,
+            Symmetry::D8 => Dice::Six,
+        }
+    }
+}
+
+struct Random {
+    x: f64,
+    y: f64,
+    width: f64,
+    height: f64,
+    hover: bool,
+    dice_state: Dice,
+}
+
+impl Btn for Random {
+    fn new(x: u32, y: u32, width: u32, height: u32) -> Self {
+        Self {
+            x: x as f64,
+            y: y as f64,
+            width: width as f64,
+            height: height as f64,
+            hover: false,
+            dice_state: Dice::One,
+        }
+    }
+
+    fn render(&self, gl: &mut GlGraphics, args: &RenderArgs) {
+        let colour = if self.hover {
+            COLOUR_HOVER
+        } else {
+            COLOUR_BUTTON
+        };
+        let pad = self.width / 5.0;
+        let btn_width = self.width - 2.0 * pad;
+        let btn_height = self.height - 2.0 * pad;
+        gl.draw(args.viewport(), |c, g| {
+            Rectangle::new_round(colour, 5.0).draw(
+                [self.x + pad, self.y + pad, btn_width, btn_height],
+                &DrawState::new_alpha(),
+                c.transform,
+                g,
+            );
+            let dot_size = 3.25;
+            match self.dice_state {
+                Dice::One => Ellipse::new(COLOUR_BACKGROUND).draw(
+                    centered([
+                        self.x + self.width / 2.0,
+                        self.y + self.height / 2.0,
+                        dot_size,
+                        dot_size,
+                    ]),
+                    &DrawState::default(),
+                    c.transform,
+                    g,
+                ),
+                Dice::Two => {
+                    for x in 1..=2 {
+                        Ellipse::new(COLOUR_BACKGROUND).draw(
+                            centered([
+                                self.x + self.height / 2.0,
+                                self.y + pad + x as f64 * (self.height - 2.0 * pad) / 3.0,
+                                dot_size,
+                                dot_size,
+                            ]),
+                            &DrawState::default(),
+                            c.transform,
+                            g,
+                        );
+                    }
+                }
+                Dice::Three => {
+                    for i in 1..=3 {
+                        Ellipse::new(COLOUR_BACKGROUND).draw(
+                            centered([
+                                self.x + pad + i as f64 * (self.width - 2.0 * pad) / 4.0,
+                                self.y + pad + i as f64 * (self.height - 2.0 * pad) / 4.0,
+                                dot_size,
+                                dot_size,
+                            ]),
+                            &DrawState::default(),
+                            c.transform,
+                            g,
+                        );
+                    }
+                }
+                Dice::Four => {
+                    for x in 1..=2 {
+                        for y in 1..=2 {
+                            Ellipse::new(COLOUR_BACKGROUND).draw(
+                                centered([
+                                    self.x + pad + x as f64 * (self.width - 2.0 * pad) / 3.0,
+                                    self.y + pad + y as f64 * (self.height - 2.0 * pad) / 3.0,
+                                    dot_size,
+                                    dot_size,
+                                ]),
+                                &DrawState::default(),
+                                c.transform,
+                                g,
+                            );
+                        }
+                    }
+                }
+                Dice::Five => {
+                    for x in 1..=3 {
+                        for y in 1..=3 {
+                            if (x != 2 && y != 2) || (x == 2 && y == 2) {
+                                Ellipse::new(COLOUR_BACKGROUND).draw(
+                                    centered([
+                                        self.x + pad + x as f64 * (self.width - 2.0 * pad) / 4.0,
+                                        self.y + pad + y as f64 * (self.height - 2.0 * pad) / 4.0,
+                                        dot_size,
+                                        dot_size,
+                                    ]),
+                                    &DrawState::default(),
+                                    c.transform,
+                                    g,
+                                );
+                            }
+                        }
+                    }
+                }
+                Dice::Six => {
+                    for x in 0..2 {
+                        for y in 0..3 {
+                            let cx = self.x + pad + (x + 1) as f64 * (btn_width / 3.0);
+                            let cy = self.y + pad + (y + 1) as f64 * ((btn_height) / 4.0);
+                            Ellipse::new(COLOUR_BACKGROUND).draw(
+                                centered([cx, cy, dot_size, dot_size]),
+                                &DrawState::new_alpha(),
+                                c.transform,
+                                g,
+                            );
+                        }
+                    }
+                }
+            }
+        });
+    }
+
+    fn mouse_cursor(&mut self, pos: [f64; 2]) {
+        self.hover = pos[0] > self.x
+            && pos[0] < self.x + self.width
+            && pos[1] > self.y
+            && pos[1] < self.y + self.height;
+    }
+
+    // Right click is handled by press_symmetry
+    fn is_pressed(&mut self, button: &Button) -> bool {
+        *button == Button::Mouse(MouseButton::Left) && self.hover
+    }
+}
+
+impl Random {
+    fn set_symmetry(&mut self, symmetry: Symmetry) {
+        self.dice_state = symmetry.into();
+    }
+
+    // Right click moves on to the next symmetry
+    fn press_symmetry(&mut self, button: &Button, symmetry: Symmetry) -> Option<Symmetry> {
+        if *button == Button::Mouse(MouseButton::Right) && self.hover {
+            let next = symmetry.next();
+            self.set_symmetry(next);
+            return Some(next);
+        }
+        None
+    }
+}
+
+impl Widget for Random {
+    fn pos(&self) -> [f64; 2] {
+        [self.x, self.y]
+    }
+
+    fn size(&self) -> [f64; 2] {
+        [self.width, self.height]
+    }
+
+    fn set_pos(&mut self, x: f64, y: f64) {
+        self.x = x;
+        self.y = y;
+    }
+
+    fn set_size(&mut self, width: f64, height: f64) {
+        self.width = width;
+        self.height = height;
+    }
+}
+
+struct Increase {
+    x: f64,
+    y: f64,
+    width: f64,
+    height: f64,
+    hover: bool,
+}
+
+impl Btn for Increase {
+    fn new(x: u32, y: u32, width: u32, height: u32) -> Self {
+        Self {
+            x: x as f64,
+            y: y as f64,
+            width: width as f64,
+            height: height as f64,
+            hover: false,
+        }
+    }
+
+    fn render(&self, gl: &mut GlGraphics, args: &RenderArgs) {
+        let colour = if self.hover {
+            COLOUR_HOVER
+        } else {
+            COLOUR_BUTTON
+        };
+        let pad = self.width / 5.0;
+        gl.draw(args.viewport(), |c, g| {
+            Polygon::new(colour).draw(
+                &[
+                    [self.x + pad, self.y + pad],
+                    [self.x + pad, self.y + self.height - pad],
+                    [self.x + self.width / 2.0, self.y + self.height / 2.0],
+                ],
+                &DrawState::new_alpha(),
+                c.transform,
+                g,
+            );
+            Polygon::new(colour).draw(
+                &[
+                    [self.x + self.width / 2.0, self.y + pad],
+                    [self.x + self.width / 2.0, self.y + self.height - pad],
+                    [self.x + self.width - pad, self.y + self.width / 2.0],
+                ],
+                &DrawState::new_alpha(),
+                c.transform,
+                g,
+            );
+        });
+    }
+
+    fn mouse_cursor(&mut self, pos: [f64; 2]) {
+        self.hover = pos[0] > self.x
+            && pos[0] < self.x + self.width
+            && pos[1] > self.y
+            && pos[1] < self.y + self.height;
+    }
+
+    fn is_pressed(&mut self, button: &Button) -> bool {
+        if *button == Button::Mouse(MouseButton::Left) && self.hover {
+            return true;
+        }
+        false
+    }
+}
+
+impl Widget for Increase {
+    fn pos(&self) -> [f64; 2] {
+        [self.x, self.y]
+    }
+
+    fn size(&self) -> [f64; 2] {
+        [self.width, self.height]
+    }
+
+    fn set_pos(&mut self, x: f64, y: f64) {
+        self.x = x;
+        self.y = y;
+    }
+
+    fn set_size(&mut self, width: f64, height: f64) {
+        self.width = width;
+        self.height = height;
+    }
+}
+
+struct Decrease {
+    x: f64,
+    y: f64,
+    width: f64,
+    height: f64,
+    hover: bool,
+}
+
+impl Btn for Decrease {
+    fn new(x: u32, y: u32, width: u32, height: u32) -> Self {
+        Self {
+            x: x as f64,
+            y: y as f64,
+            width: width as f64,
+            height: height as f64,
+            hover: false,
+        }
+    }
+
+    fn render(&self, gl: &mut GlGraphics, args: &RenderArgs) {
+        let colour = if self.hover {
+            COLOUR_HOVER
+        } else {
+            COLOUR_BUTTON
+        };
+        let pad = self.width / 5.0;
+        gl.draw(args.viewport(), |c, g| {
+            Polygon::new(colour).draw(
+                &[
+                    [self.x + self.width / 2.0, self.y + pad],
+                    [self.x + self.width / 2.0, self.y + self.height - pad],
+                    [self.x + pad, self.y + self.height / 2.0],
+                ],
+                &DrawState::new_alpha(),
+                c.transform,
+                g,
+            );
+            Polygon::new(colour).draw(
+                &[
+                    [self.x + self.width - pad, self.y + pad],
+                    [self.x + self.width - pad, self.y + self.height - pad],
+                    [self.x + self.width / 2.0, self.y + self.width / 2.0],
+                ],
+                &DrawState::new_alpha(),
+                c.transform,
+                g,
+            );
+        });
+    }
+
+    fn mouse_cursor(&mut self, pos: [f64; 2]) {
+        self.hover = pos[0] > self.x
+            && pos[0] < self.x + self.width
+            && pos[1] > self.y
+            && pos[1] < self.y + self.height;
+    }
+
+    fn is_pressed(&mut self, button: &Button) -> bool {
+        if *button == Button::Mouse(MouseButton::Left) && self.hover {
+            return true;
+        }
+        false
+    }
+}
+
+impl Widget for Decrease {
+    fn pos(&self) -> [f64; 2] {
+        [self.x, self.y]
+    }
+
+    fn size(&self) -> [f64; 2] {
+        [self.width, self.height]
+    }
+
+    fn set_pos(&mut self, x: f64, y: f64) {
+        self.x = x;
+        self.y = y;
+    }
+
+    fn set_size(&mut self, width: f64, height: f64) {
+        self.width = width;
+        self.height = height;
+    }
+}
+
+// Picks the drawing tool used on the grid, the selected one is highlighted
+struct ToolButton {
+    x: f64,
+    y: f64,
+    width: f64,
+    height: f64,
+    hover: bool,
+    tool: Tool,
+    selected: bool,
+}
+
+impl ToolButton {
+    fn with_tool(x: u32, y: u32, width: u32, height: u32, tool: Tool) -> Self {
+        Self {
+            tool,
+            ..Self::new(x, y, width, height)
+        }
+    }
+}
+
+impl Btn for ToolButton {
+    fn new(x: u32, y: u32, width: u32, height: u32) -> Self {
+        Self {
+            x: x as f64,
+            y: y as f64,
+            width: width as f64,
+            height: height as f64,
+            hover: false,
+            tool: Tool::Pen,
+            selected: false,
+        }
+    }
+
+    fn render(&self, gl: &mut GlGraphics, args: &RenderArgs) {
+        let colour = if self.hover {
+            COLOUR_HOVER
+        } else {
+            COLOUR_BUTTON
+        };
+        let pad = self.width.min(self.height) / 4.0;
+        let (left, top) = (self.x + pad, self.y + pad);
+        let (right, bottom) = (self.x + self.width - pad, self.y + self.height - pad);
+        let icon = [left, top, right - left, bottom - top];
+        gl.draw(args.viewport(), |c, g| {
+            let draw_state = &DrawState::new_alpha();
+            if self.selected {
+                Rectangle::new(COLOUR_CURVE).draw(
+                    [self.x, self.y, self.width, self.height],
+                    draw_state,
+                    c.transform,
+                    g,
+                );
+            }
+            match self.tool {
+                Tool::Pen => {
+                    let third = (right - left) / 3.0;
+                    for i in 0..3 {
+                        let (from, to) = if i % 2 == 0 {
+                            (bottom, top)
+                        } else {
+                            (top, bottom)
+                        };
+                        let x = left + i as f64 * third;
+                        Line::new(colour, 1.5).draw(
+                            [x, from, x + third, to],
+                            draw_state,
+                            c.transform,
+                            g,
+                        );
+                    }
+                }
+                Tool::Line => Line::new(colour, 1.5).draw(
+                    [left, bottom, right, top],
+                    draw_state,
+                    c.transform,
+                    g,
+                ),
+                Tool::Rectangle => {
+                    Rectangle::new_border(colour, 1.5).draw(icon, draw_state, c.transform, g)
+                }
+                Tool::FilledRectangle => {
+                    Rectangle::new(colour).draw(icon, draw_state, c.transform, g)
+                }
+                Tool::Ellipse => {
+                    Ellipse::new_border(colour, 1.5).draw(icon, draw_state, c.transform, g)
+                }
+                Tool::FilledEllipse => Ellipse::new(colour).draw(icon, draw_state, c.transform, g),
+                Tool::Fill => {
+                    // A drop
+                    let radius = (right - left) / 3.0;
+                    let centre = left + (right - left) / 2.0;
+                    Polygon::new(colour).draw(
+                        &[
+                            [centre, top],
+                            [centre - radius, bottom - radius],
+                            [centre + radius, bottom - radius],
+                        ],
+                        draw_state,
+                        c.transform,
+                        g,
+                    );
+                    Ellipse::new(colour).draw(
+                        centered([centre, bottom - radius, radius, radius]),
+                        draw_state,
+                        c.transform,
+                        g,
+                    );
+                }
+            }
+        });
+    }
+
+    fn mouse_cursor(&mut self, pos: [f64; 2]) {
+        self.hover = pos[0] > self.x
+            && pos[0] < self.x + self.width
+            && pos[1] > self.y
+            && pos[1] < self.y + self.height;
+    }
+
+    fn is_pressed(&mut self, button: &Button) -> bool {
+        if *button == Button::Mouse(MouseButton::Left) && self.hover {
+            return true;
+        }
+        false
+    }
+}
+
+impl Widget for ToolButton {
+    fn pos(&self) -> [f64; 2] {
+        [self.x, self.y]
+    }
+
+    fn size(&self) -> [f64; 2] {
+        [self.width, self.height]
+    }
+
+    fn set_pos(&mut self, x: f64, y: f64) {
+        self.x = x;
+        self.y = y;
+    }
+
+    fn set_size(&mut self, width: f64, height: f64) {
+        self.width = width;
+        self.height = height;
+    }
+}
+
+struct Speed {
+    x: f64,
+    y: f64,
+    width: f64,
+    height: f64,
+    speed: usize,
+    min: usize,
+    max: usize,
+    font_size: FontSize,
+}
+
+impl Speed {
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+        speed: usize,
+        min: usize,
+        max: usize,
+        font_size: FontSize,
+    ) -> Self {
+        Self {
+            x: x as f64,
+            y: y as f64,
+            width: width as f64,
+            height: height as f64,
+            speed,
+            min,
+            max,
+            font_size,
+        }
+    }
+
+    fn render(&self, gl: &mut GlGraphics, args: &RenderArgs, glyph_cache: &mut GlyphCache) {
+        let text = format!("{}x", self.speed);
+        let x = self.x + self.width / 2.0
+            - glyph_cache
+                .width(self.font_size, &text)
+                .expect("Unable to measure text") as f64
+                / 2.0;
+        let y = self.y + self.height / 2.0 + self.font_size as f64 / 2.0 - 5.0;
+        gl.draw(args.viewport(), |c, g| {
+            Text::new_color(COLOUR_BUTTON, self.font_size)
+                .draw(
+                    &text,
+                    glyph_cache,
+                    &DrawState::default(),
+                    c.transform.trans(x, y),
+                    g,
+                )
+                .expect("Unable to draw text");
+        });
+    }
+
+    fn increase(&mut self) {
+        if self.speed < self.max {
+            self.speed *= 2;
+        }
+    }
+
+    fn decrease(&mut self) {
+        if self.speed > self.min {
+            self.speed /= 2;
+        }
+    }
+}
+
+impl Widget for Speed {
+    fn pos(&self) -> [f64; 2] {
+        [self.x, self.y]
+    }
+
+    fn size(&self) -> [f64; 2] {
+        [self.width, self.height]
+    }
+
+    fn set_pos(&mut self, x: f64, y: f64) {
+        self.x = x;
+        self.y = y;
+    }
+
+    fn set_size(&mut self, width: f64, height: f64) {
+        self.width = width;
+        self.height = height;
+    }
+}
+
+struct RuleSelect {
+    x: f64,
+    y: f64,
+    width: f64,
+    height: f64,
+    hover: bool,
+    preset: usize,
+    text: String,
+    editing: bool,
+    error: Option<RuleParseError>,
+    font_size: FontSize,
+}
+
+impl RuleSelect {
+    fn new(x: u32, y: u32, width: u32, height: u32, font_size: FontSize) -> Self {
+        Self {
+            x: x as f64,
+            y: y as f64,
+            width: width as f64,
+            height: height as f64,
+            hover: false,
+            preset: 0,
+            text: RULE_PRESETS[0].to_string(),
+            editing: false,
+            error: None,
+            font_size,
+        }
+    }
+
+    fn render(&self, gl: &mut GlGraphics, args: &RenderArgs, glyph_cache: &mut GlyphCache) {
+        let colour = if self.hover || self.editing {
+            COLOUR_HOVER
+        } else {
+            COLOUR_BUTTON
+        };
+        let text = if self.editing {
+            format!("{}_", self.text)
+        } else {
+            self.text.clone()
+        };
+        let x = self.x + self.width / 2.0
+            - glyph_cache
+                .width(self.font_size, &text)
+                .expect("Unable to measure text") as f64
+                / 2.0;
+        let y = self.y + self.height / 2.0 + self.font_size as f64 / 2.0 - 5.0;
+        gl.draw(args.viewport(), |c, g| {
+            Text::new_color(colour, self.font_size)
+                .draw(
+                    &text,
+                    glyph_cache,
+                    &DrawState::default(),
+                    c.transform.trans(x, y),
+                    g,
+                )
+                .expect("Unable to draw text");
+        });
+        if let Some(error) = &self.error {
+            let error_size = self.font_size / 2;
+            let message = error.to_string();
+            let x = self.x + self.width / 2.0
+                - glyph_cache
+                    .width(error_size, &message)
+                    .expect("Unable to measure text")
+                    / 2.0;
+            let y = self.y + self.height - 4.0;
+            gl.draw(args.viewport(), |c, g| {
+                Text::new_color(COLOUR_REMOVE, error_size)
+                    .draw(
+                        &message,
+                        glyph_cache,
+                        &DrawState::default(),
+                        c.transform.trans(x, y),
+                        g,
+                    )
+                    .expect("Unable to draw text");
+            });
+        }
+    }
+
+    fn mouse_cursor(&mut self, pos: [f64; 2]) {
+        self.hover = pos[0] > self.x
+            && pos[0] < self.x + self.width
+            && pos[1] > self.y
+            && pos[1] < self.y + self.height;
+    }
+
+    // Left click cycles through the presets, right click starts typing a custom rule
+    fn press(&mut self, button: &Button) -> Option<Rule> {
+        match button {
+            Button::Mouse(MouseButton::Left) if self.hover => {
+                if !self.editing {
+                    self.preset = (self.preset + 1) % RULE_PRESETS.len();
+                }
+                self.editing = false;
+                self.error = None;
+                self.text = RULE_PRESETS[self.preset].to_string();
+                self.text.parse().ok()
+            }
+            Button::Mouse(MouseButton::Right) if self.hover => {
+                self.editing = true;
+                self.error = None;
+                self.text.clear();
+                None
+            }
+            Button::Keyboard(Key::Backspace) if self.editing => {
+                self.text.pop();
+                None
+            }
+            Button::Keyboard(Key::Return) if self.editing => match self.text.parse::<Rule>() {
+                Ok(rule) => {
+                    self.editing = false;
+                    self.error = None;
+                    self.text = rule.to_string();
+                    Some(rule)
+                }
+                Err(error) => {
+                    self.error = Some(error);
+                    None
+                }
+            },
+            _ => None,
+        }
+    }
+
+    // Shows a rule chosen elsewhere, such as one read from a pattern file
+    fn set_rule(&mut self, rule: Rule) {
+        self.editing = false;
+        self.error = None;
+        self.text = rule.to_string();
+    }
+
+    fn text(&mut self, text: &str) {
+        if self.editing {
+            self.text.extend(text.chars().filter(|c| !c.is_control()));
+        }
+    }
+}
+
+impl Widget for RuleSelect {
+    fn pos(&self) -> [f64; 2] {
+        [self.x, self.y]
+    }
+
+    fn size(&self) -> [f64; 2] {
+        [self.width, self.height]
+    }
+
+    fn set_pos(&mut self, x: f64, y: f64) {
+        self.x = x;
+        self.y = y;
+    }
+
+    fn set_size(&mut self, width: f64, height: f64) {
+        self.width = width;
+        self.height = height;
+    }
+}
+
+struct Choice {
+    x: f64,
+    y: f64,
+    width: f64,
+    height: f64,
+    hover: bool,
+    options: Vec<String>,
+    selected: usize,
+    font_size: FontSize,
+}
+
+impl Choice {
+    fn new(
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+        options: Vec<String>,
+        font_size: FontSize,
+    ) -> Self {
+        Self {
+            x: x as f64,
+            y: y as f64,
+            width: width as f64,
+            height: height as f64,
+            hover: false,
+            options,
+            selected: 0,
+            font_size,
+        }
+    }
+
+    fn select(&mut self, index: usize) {
+        self.selected = index;
+    }
+
+    fn render(&self, gl: &mut GlGraphics, args: &RenderArgs, glyph_cache: &mut GlyphCache) {
+        let colour = if self.hover {
+            COLOUR_HOVER
+        } else {
+            COLOUR_BUTTON
+        };
+        let text = &self.options[self.selected];
+        let x = self.x + self.width / 2.0
+            - glyph_cache
+                .width(self.font_size, text)
+                .expect("Unable to measure text")
+                / 2.0;
+        let y = self.y + self.height / 2.0 + self.font_size as f64 / 2.0 - 5.0;
+        gl.draw(args.viewport(), |c, g| {
+            Text::new_color(colour, self.font_size)
+                .draw(
+                    text,
+                    glyph_cache,
+                    &DrawState::default(),
+                    c.transform.trans(x, y),
+                    g,
+                )
+                .expect("Unable to draw text");
+        });
+    }
+
+    fn mouse_cursor(&mut self, pos: [f64; 2]) {
+        self.hover = pos[0] > self.x
+            && pos[0] < self.x + self.width
+            && pos[1] > self.y
+            && pos[1] < self.y + self.height;
+    }
+
+    // Clicking cycles to the next option and returns its index
+    fn press(&mut self, button: &Button) -> Option<usize> {
+        if *button == Button::Mouse(MouseButton::Left) && self.hover {
+            self.selected = (self.selected + 1) % self.options.len();
+            return Some(self.selected);
+        }
+        None
+    }
+}
+
+impl Widget for Choice {
+    fn pos(&self) -> [f64; 2] {
+        [self.x, self.y]
+    }
+
+    fn size(&self) -> [f64; 2] {
+        [self.width, self.height]
+    }
+
+    fn set_pos(&mut self, x: f64, y: f64) {
+        self.x = x;
+        self.y = y;
+    }
+
+    fn set_size(&mut self, width: f64, height: f64) {
+        self.width = width;
+        self.height = height;
+    }
+}
+
+struct Label {
+    x: f64,
+    y: f64,
+    width: f64,
+    height: f64,
+    text: String,
+    font_size: FontSize,
+}
+
+impl Label {
+    fn new(x: u32, y: u32, width: u32, height: u32, font_size: FontSize) -> Self {
+        Self {
+            x: x as f64,
+            y: y as f64,
+            width: width as f64,
+            height: height as f64,
+            text: String::new(),
+            font_size,
+        }
+    }
+
+    fn render(&self, gl: &mut GlGraphics, args: &RenderArgs, glyph_cache: &mut GlyphCache) {
+        let x = self.x + self.width / 2.0
+            - glyph_cache
+                .width(self.font_size, &self.text)
+                .expect("Unable to measure text")
+                / 2.0;
+        let y = self.y + self.height / 2.0 + self.font_size as f64 / 2.0 - 3.0;
+        gl.draw(args.viewport(), |c, g| {
+            Text::new_color(COLOUR_HOVER, self.font_size)
+                .draw(
+                    &self.text,
+                    glyph_cache,
+                    &DrawState::default(),
+                    c.transform.trans(x, y),
+                    g,
+                )
+                .expect("Unable to draw text");
+        });
+    }
+
+    fn set_text(&mut self, text: String) {
+        self.text = text;
+    }
+}
+
+impl Widget for Label {
+    fn pos(&self) -> [f64; 2] {
+        [self.x, self.y]
+    }
+
+    fn size(&self) -> [f64; 2] {
+        [self.width, self.height]
+    }
+
+    fn set_pos(&mut self, x: f64, y: f64) {
+        self.x = x;
+        self.y = y;
+    }
+
+    fn set_size(&mut self, width: f64, height: f64) {
+        self.width = width;
+        self.height = height;
+    }
+}
+
+// Recorded history drawn as a population curve behind a slider, dragging the
+// slider seeks through the run
+struct Timeline {
+    x: f64,
+    y: f64,
+    width: f64,
+    height: f64,
+    hover: bool,
+    dragging: bool,
+    // Generations since the oldest recorded state and population, for each state
+    points: Vec<(u64, u64)>,
+    position: usize,
+    generation: u64,
+    font_size: FontSize,
+}
+
+impl Timeline {
+    fn new(x: u32, y: u32, width: u32, height: u32, font_size: FontSize) -> Self {
+        Self {
+            x: x as f64,
+            y: y as f64,
+            width: width as f64,
+            height: height as f64,
+            hover: false,
+            dragging: false,
+            points: vec![(0, 0)],
+            position: 0,
+            generation: 0,
+            font_size,
+        }
+    }
+
+    fn set_history(&mut self, points: Vec<(u64, u64)>, position: usize, generation: u64) {
+        self.points = points;
+        self.position = position;
+        self.generation = generation;
+    }
+
+    fn length(&self) -> u64 {
+        self.points.last().map_or(0, |&(generation, _)| generation)
+    }
+
+    fn x_of(&self, generation: u64) -> f64 {
+        let pad = self.height / 5.0;
+        let length = self.length().max(1) as f64;
+        self.x + pad + (self.width - 2.0 * pad) * generation as f64 / length
+    }
+
+    // Nearest recorded state, the latest one when edits share a generation
+    fn position_at(&self, x: f64) -> usize {
+        let mut best = (f64::MAX, 0);
+        for (index, &(generation, _)) in self.points.iter().enumerate() {
+            let distance = (self.x_of(generation) - x).abs();
+            if distance <= best.0 {
+                best = (distance, index);
+            }
+        }
+        best.1
+    }
+
+    fn render(&self, gl: &mut GlGraphics, args: &RenderArgs, glyph_cache: &mut GlyphCache) {
+        let pad = self.height / 5.0;
+        let bottom = self.y + self.height - pad;
+        let curve_height = self.height - 2.0 * pad;
+        let max_population = self
+            .points
+            .iter()
+            .map(|&(_, population)| population)
+            .max()
+            .unwrap_or(0)
+            .max(1);
+        // Tallest population per pixel column so long runs don't overdraw
+        let mut columns = vec![None; self.width as usize + 1];
+        for &(generation, population) in &self.points {
+            let column = (self.x_of(generation) - self.x) as usize;
+            let slot: &mut Option<u64> = &mut columns[column.min(self.width as usize)];
+            *slot = Some(slot.map_or(population, |p| p.max(population)));
+        }
+        let colour = if self.hover || self.dragging {
+            COLOUR_HOVER
+        } else {
+            COLOUR_BUTTON
+        };
+        let start = self.generation.saturating_sub(self.points[self.position].0);
+        let end = start + self.length();
+        let end_text = end.to_string();
+        let end_width = glyph_cache
+            .width(self.font_size, &end_text)
+            .expect("Unable to measure text");
+        let text_y = self.y + self.height / 2.0 + self.font_size as f64 / 2.0 - 3.0;
+        gl.draw(args.viewport(), |c, g| {
+            for (column, population) in columns.iter().enumerate() {
+                if let Some(population) = population {
+                    let x = self.x + column as f64;
+                    let top = bottom - curve_height * *population as f64 / max_population as f64;
+                    Line::new(COLOUR_CURVE, 0.5).draw(
+                        [x, bottom, x, top],
+                        &DrawState::new_alpha(),
+                        c.transform,
+                        g,
+                    );
+                }
+            }
+            Line::new(colour, 0.5).draw(
+                [self.x + pad, bottom, self.x + self.width - pad, bottom],
+                &DrawState::new_alpha(),
+                c.transform,
+                g,
+            );
+            let x = self.x_of(self.points[self.position].0);
+            Rectangle::new(colour).draw(
+                [x - 2.0, self.y + pad / 2.0, 4.0, self.height - pad],
+                &DrawState::new_alpha(),
+                c.transform,
+                g,
+            );
+            for (text, x) in [
+                (start.to_string(), self.x + pad),
+                (end_text, self.x + self.width - pad - end_width),
+            ] {
+                Text::new_color(COLOUR_HOVER, self.font_size)
+                    .draw(
+                        &text,
+                        glyph_cache,
+                        &DrawState::default(),
+                        c.transform.trans(x, text_y),
+                        g,
+                    )
+                    .expect("Unable to draw text");
+            }
+        });
+    }
+
+    // Returns the state to seek to while the slider is being dragged
+    fn mouse_cursor(&mut self, pos: [f64; 2]) -> Option<usize> {
+        self.hover = pos[0] > self.x
+            && pos[0] < self.x + self.width
+            && pos[1] > self.y
+            && pos[1] < self.y + self.height;
+        self.dragging.then(|| self.position_at(pos[0]))
+    }
+
+    fn press(&mut self, button: &Button, pos: [f64; 2]) -> Option<usize> {
+        if Button::Mouse(MouseButton::Left) == *button && self.hover {
+            self.dragging = true;
+            Some(self.position_at(pos[0]))
+        } else {
+            None
+        }
+    }
+
+    fn release(&mut self, button: &Button) {
+        if Button::Mouse(MouseButton::Left) == *button {
+            self.dragging = false;
+        }
+    }
+}
+
+impl Widget for Timeline {
+    fn pos(&self) -> [f64; 2] {
+        [self.x, self.y]
+    }
+
+    fn size(&self) -> [f64; 2] {
+        [self.width, self.height]
+    }
+
+    fn set_pos(&mut self, x: f64, y: f64) {
+        self.x = x;
+        self.y = y;
+    }
+
+    fn set_size(&mut self, width: f64, height: f64) {
+        self.width = width;
+        self.height = height;
+    }
+}
+
+// Side panel listing the built in patterns by category, with a preview and the
+// description of the hovered or selected one. Clicking the category cycles it.
+struct Library {
+    x: f64,
+    y: f64,
+    width: f64,
+    height: f64,
+    category: usize,
+    hover: Option<usize>,
+    header_hover: bool,
+    selected: Option<&'static library::Entry>,
+    // Every entry parsed once for the preview, by name
+    previews: HashMap<&'static str, Pattern>,
+    // Rows of the longest category, the preview goes below them
+    list_rows: usize,
+    font_size: FontSize,
+}
+
+impl Library {
+    const HEADER_HEIGHT: f64 = 40.0;
+    const ROW_HEIGHT: f64 = 26.0;
+    const PAD: f64 = 10.0;
+
+    fn new(x: u32, y: u32, width: u32, height: u32, font_size: FontSize) -> Self {
+        Self {
+            x: x as f64,
+            y: y as f64,
+            width: width as f64,
+            height: height as f64,
+            category: 0,
+            hover: None,
+            header_hover: false,
+            selected: None,
+            previews: library::PATTERNS
+                .iter()
+                .map(|entry| (entry.name, entry.pattern()))
+                .collect(),
+            list_rows: Category::ALL
+                .into_iter()
+                .map(|category| library::in_category(category).count())
+                .max()
+                .unwrap_or(0),
+            font_size,
+        }
+    }
+
+    fn entries(&self) -> Vec<&'static library::Entry> {
+        library::in_category(Category::ALL[self.category]).collect()
+    }
+
+    fn render(&self, gl: &mut GlGraphics, args: &RenderArgs, glyph_cache: &mut GlyphCache) {
+        let entries = self.entries();
+        let list_top = self.y + Self::HEADER_HEIGHT;
+        let preview_top = list_top + self.list_rows as f64 * Self::ROW_HEIGHT + Self::PAD;
+        let preview_size = self.width - 2.0 * Self::PAD;
+        let shown = self
+            .hover
+            .and_then(|row| entries.get(row).copied())
+            .or(self.selected);
+
+        // Description wrapped to the panel, measured before drawing borrows the cache
+        let mut lines: Vec<String> = Vec::new();
+        if let Some(entry) = shown {
+            for word in entry.description.split_whitespace() {
+                let line = match lines.last() {
+                    Some(last) => format!("{} {}", last, word),
+                    None => word.to_string(),
+                };
+                let fits = glyph_cache
+                    .width(self.font_size, &line)
+                    .expect("Unable to measure text")
+                    <= preview_size;
+                match lines.last_mut() {
+                    Some(last) if fits => *last = line,
+                    _ => lines.push(word.to_string()),
+                }
+            }
+        }
+        let header = format!("< {} >", Category::ALL[self.category]);
+        let header_x = self.x + self.width / 2.0
+            - glyph_cache
+                .width(self.font_size, &header)
+                .expect("Unable to measure text")
+                / 2.0;
+
+        gl.draw(args.viewport(), |c, g| {
+            let draw_state = &DrawState::default();
+            Line::new(COLOUR_CURVE, 0.5).draw(
+                [self.x, self.y, self.x, self.y + self.height],
+                draw_state,
+                c.transform,
+                g,
+            );
+            let colour = if self.header_hover {
+                COLOUR_HOVER
+            } else {
+                COLOUR_BUTTON
+            };
+            Text::new_color(colour, self.font_size)
+                .draw(
+                    &header,
+                    glyph_cache,
+                    draw_state,
+                    c.transform.trans(
+                        header_x,
+                        self.y + Self::HEADER_HEIGHT / 2.0 + self.font_size as f64 / 2.0 - 3.0,
+                    ),
+                    g,
+                )
+                .expect("Unable to draw text");
+
+            for (row, entry) in entries.iter().enumerate() {
+                let top = list_top + row as f64 * Self::ROW_HEIGHT;
+                if self
+                    .selected
+                    .is_some_and(|selected| std::ptr::eq(selected, *entry))
+                {
+                    Rectangle::new(COLOUR_CURVE).draw(
+                        [self.x + 1.0, top, self.width - 1.0, Self::ROW_HEIGHT],
+                        draw_state,
+                        c.transform,
+                        g,
+                    );
+                }
+                let colour = if self.hover == Some(row) {
+                    COLOUR_HOVER
+                } else {
+                    COLOUR_BUTTON
+                };
+                Text::new_color(colour, self.font_size)
+                    .draw(
+                        entry.name,
+                        glyph_cache,
+                        draw_state,
+                        c.transform.trans(
+                            self.x + Self::PAD,
+                            top + Self::ROW_HEIGHT / 2.0 + self.font_size as f64 / 2.0 - 3.0,
+                        ),
+                        g,
+                    )
+                    .expect("Unable to draw text");
+            }
+
+            let preview = [self.x + Self::PAD, preview_top, preview_size, preview_size];
+            Rectangle::new_border(COLOUR_CURVE, 0.5).draw(preview, draw_state, c.transform, g);
+            let Some(entry) = shown else {
+                return;
+            };
+            // Thumbnail scaled to fit and centred, cells no bigger than 12 pixels
+            let pattern = &self.previews[entry.name];
+            let size = (preview_size / pattern.width.max(pattern.height) as f64).min(12.0);
+            let left = preview[0] + (preview_size - pattern.width as f64 * size) / 2.0;
+            let top = preview[1] + (preview_size - pattern.height as f64 * size) / 2.0;
+            for &(x, y) in &pattern.cells {
+                Rectangle::new(COLOUR_ALIVE_CELL).draw(
+                    [left + x as f64 * size, top + y as f64 * size, size, size],
+                    draw_state,
+                    c.transform,
+                    g,
+                );
+            }
+            for (i, line) in lines.iter().enumerate() {
+                let y = preview_top
+                    + preview_size
+                    + Self::PAD
+                    + (i + 1) as f64 * (self.font_size as f64 + 4.0);
+                Text::new_color(COLOUR_HOVER, self.font_size)
+                    .draw(
+                        line,
+                        glyph_cache,
+                        draw_state,
+                        c.transform.trans(self.x + Self::PAD, y),
+                        g,
+                    )
+                    .expect("Unable to draw text");
+            }
+        });
+    }
+
+    fn mouse_cursor(&mut self, pos: [f64; 2]) {
+        let inside = pos[0] > self.x && pos[0] < self.x + self.width;
+        self.header_hover = inside && pos[1] > self.y && pos[1] < self.y + Self::HEADER_HEIGHT;
+        let row = (pos[1] - self.y - Self::HEADER_HEIGHT) / Self::ROW_HEIGHT;
+        self.hover = (inside && row >= 0.0)
+            .then_some(row as usize)
+            .filter(|&row| row < self.entries().len());
+    }
+
+    // Left click on the category goes to the next one and right click back, a click
+    // on a pattern returns it to be stamped
+    fn press(&mut self, button: &Button) -> Option<&'static library::Entry> {
+        match button {
+            Button::Mouse(MouseButton::Left) if self.header_hover => {
+                self.category = (self.category + 1) % Category::ALL.len();
+                None
+            }
+            Button::Mouse(MouseButton::Right) if self.header_hover => {
+                self.category = (self.category + Category::ALL.len() - 1) % Category::ALL.len();
+                None
+            }
+            Button::Mouse(MouseButton::Left) => {
+                let entry = self.entries()[self.hover?];
+                self.selected = Some(entry);
+                Some(entry)
+            }
+            _ => None,
+        }
+    }
+}
+
+impl Widget for Library {
+    fn pos(&self) -> [f64; 2] {
+        [self.x, self.y]
+    }
+
+    fn size(&self) -> [f64; 2] {
+        [self.width, self.height]
+    }
+
+    fn set_pos(&mut self, x: f64, y: f64) {
+        self.x = x;
+        self.y = y;
+    }
+
+    fn set_size(&mut self, width: f64, height: f64) {
+        self.width = width;
+        self.height = height;
+    }
+}
+
+// Opens the window, args are the command line after the program name
+pub fn run(args: Vec<String>) {
+    let opengl = OpenGL::V3_2;
+    let window: &mut GlutinWindow = &mut WindowSettings::new("Gol", [WIDTH + PANEL_WIDTH, HEIGHT])
+        .graphics_api(opengl)
+        .exit_on_esc(true)
+        .build()
+        .expect("Unable to build window");
+
+    let mut events = Events::new(EventSettings::new());
+    let mut gl = GlGraphics::new(opengl);
+    let mut glyph_cache =
+        GlyphCache::new("fonts/Nexa-Heavy.ttf", (), TextureSettings::new()).unwrap();
+
+    // Undo history is capped at 64 MiB unless given with --history-memory MIB
+    let mut args = args;
+    let history_budget = take_option(&mut args, "--history-memory", "a size in MiB")
+        .map_or(HISTORY_MEMORY_BUDGET, |mib: usize| mib * 1024 * 1024);
+    // HashLife's node cache gets 256 MiB unless given with --hashlife-memory MIB
+    let hashlife_budget = take_option(&mut args, "--hashlife-memory", "a size in MiB")
+        .map_or(HASHLIFE_MEMORY_BUDGET, |mib: usize| mib * 1024 * 1024);
+    // Settings of the soup Shift+R makes, to regenerate one from its seed
+    let seed: Option<u64> = take_option(&mut args, "--seed", "a number");
+    let density: Option<u32> = take_option(&mut args, "--density", "a percentage");
+    let symmetry: Option<Symmetry> =
+        take_option(&mut args, "--symmetry", "one of none, C2, D2, C4, D4 or D8");
+
+    // Board size can be given as COLSxROWS and the backend by name on the command
+    // line, anything else is a pattern file to load
+    let (options, patterns): (Vec<String>, Vec<String>) = args
+        .into_iter()
+        .partition(|arg| parse_size(arg).is_some() || Backend::from_name(arg).is_some());
+    let [cols, rows] = options
+        .iter()
+        .rev()
+        .find_map(|arg| parse_size(arg))
+        .unwrap_or([50, 50]);
+    let backend = options
+        .iter()
+        .rev()
+        .find_map(|arg| Backend::from_name(arg))
+        .unwrap_or(Backend::Dense);
+    let mut grid = Grid::new(0, 80, 500, 500, cols, rows);
+    grid.hashlife_budget = hashlife_budget;
+    grid.set_backend(backend);
+    grid.history = History::new(history_budget);
+    grid.soup.seed = seed.unwrap_or(grid.soup.seed);
+    grid.soup.density = density.unwrap_or(grid.soup.density).min(100);
+    grid.soup.symmetry = symmetry.unwrap_or(grid.soup.symmetry);
+    let mut step_back = StepBack::new((WIDTH / 2) - 200, 0, 50, 50);
+    let mut next = Next::new((WIDTH / 2) - 150, 0, 50, 50);
+    let mut jump = Jump::new((WIDTH / 2) - 100, 0, 50, 50, 10, 1, HashLife::MAX_STEP_LOG);
+    jump.set_max(backend.max_step_log());
+    let mut play = Play::new((WIDTH / 2) - 100, 0, 50, 50);
+    let mut random = Random::new(WIDTH / 2 - 50, 0, 50, 50);
+    let mut decrease = Decrease::new(WIDTH / 2, 0, 50, 50);
+    let mut speed = Speed::new(WIDTH / 2 + 50, 0, 50, 50, 4, 1, 16, 30);
+    let mut increase = Increase::new((WIDTH / 2) + 100, 0, 50, 50);
+    let mut timeline = Timeline::new(0, 50, WIDTH, 30, 12);
+    let mut tool_buttons: Vec<ToolButton> = Tool::ALL
+        .iter()
+        .map(|&tool| ToolButton::with_tool(0, 580, 40, 40, tool))
+        .collect();
+    tool_buttons[0].selected = true;
+    let mut rule_select = RuleSelect::new(0, 620, WIDTH, 50, 20);
+    let mut topology_select = Choice::new(
+        0,
+        600,
+        WIDTH / 2,
+        50,
+        Topology::ALL.iter().map(|t| t.to_string()).collect(),
+        16,
+    );
+    let mut backend_select = Choice::new(
+        WIDTH / 2,
+        600,
+        WIDTH / 2,
+        50,
+        Backend::ALL.iter().map(|b| b.name().to_string()).collect(),
+        16,
+    );
+    let mut threads_select = Choice::new(
+        0,
+        600,
+        WIDTH / 3,
+        50,
+        vec![
+            "1 thread".to_string(),
+            format!("{} threads", rayon::current_num_threads()),
+        ],
+        16,
+    );
+    let mut tracking_select = Choice::new(
+        0,
+        600,
+        WIDTH / 4,
+        50,
+        vec!["Full scan".to_string(), "Active tiles".to_string()],
+        16,
+    );
+    let mut status = Label::new(0, 720, WIDTH, 30, 14);
+    let mut library = Library::new(WIDTH, 0, PANEL_WIDTH, HEIGHT, 14);
+    let mut button_row_items: [&mut dyn Widget; 8] = [
+        &mut step_back,
+        &mut next,
+        &mut jump,
+        &mut play,
+        &mut random,
+        &mut decrease,
+        &mut speed,
+        &mut increase,
+    ];
+    let _ = HGroup::new(
+        (WIDTH / 2) as f64 - (button_row_items.len() as f64 * 50.0 / 2.0),
+        0.0,
+        button_row_items.len() as f64 * 50.0,
+        50.0,
+        &mut button_row_items,
+    );
+    let mut tool_row_items: Vec<&mut dyn Widget> = tool_buttons
+        .iter_mut()
+        .map(|button| button as &mut dyn Widget)
+        .collect();
+    let _ = HGroup::new(
+        (WIDTH / 2) as f64 - (tool_row_items.len() as f64 * 40.0 / 2.0),
+        580.0,
+        tool_row_items.len() as f64 * 40.0,
+        40.0,
+        &mut tool_row_items,
+    );
+    let _ = HGroup::new(
+        0.0,
+        670.0,
+        WIDTH as f64,
+        50.0,
+        &mut [
+            &mut topology_select,
+            &mut backend_select,
+            &mut threads_select,
+            &mut tracking_select,
+        ],
+    );
+
+    backend_select.select(
+        Backend::ALL
+            .iter()
+            .position(|&b| b == backend)
+            .expect("Missing backend"),
+    );
+
+    for path in patterns {
+        if grid.load(Path::new(&path)).is_some() {
+            rule_select.set_rule(grid.universe.rule());
+        }
+    }
+
+    let mut mouse_pos = [0.0, 0.0];
+    let mut ctrl = false;
+    let mut playing = false;
+    let mut last_tick = SystemTime::now();
+
+    while let Some(e) = events.next(window) {
+        if let Some(args) = e.render_args() {
+            gl.draw(args.viewport(), |_c, g| {
+                clear(COLOUR_BACKGROUND, g);
+            });
+            grid.render(&mut gl, &args);
+            timeline.set_history(
+                grid.history.timeline(),
+                grid.history.position(),
+                grid.generation,
+            );
+            timeline.render(&mut gl, &args, &mut glyph_cache);
+            step_back.render(&mut gl, &args);
+            next.render(&mut gl, &args);
+            jump.render(&mut gl, &args, &mut glyph_cache);
+            play.render(&mut gl, &args);
+            random.set_symmetry(grid.soup.symmetry);
+            random.render(&mut gl, &args);
+            decrease.render(&mut gl, &args);
+            increase.render(&mut gl, &args);
+            speed.render(&mut gl, &args, &mut glyph_cache);
+            for button in &tool_buttons {
+                button.render(&mut gl, &args);
+            }
+            rule_select.render(&mut gl, &args, &mut glyph_cache);
+            topology_select.render(&mut gl, &args, &mut glyph_cache);
+            backend_select.render(&mut gl, &args, &mut glyph_cache);
+            threads_select.render(&mut gl, &args, &mut glyph_cache);
+            tracking_select.render(&mut gl, &args, &mut glyph_cache);
+            status.set_text(grid.status());
+            status.render(&mut gl, &args, &mut glyph_cache);
+            library.render(&mut gl, &args, &mut glyph_cache);
+        }
+
+        if let Some(pos) = e.mouse_cursor_args() {
+            mouse_pos = pos;
+            grid.mouse_cursor(pos);
+            if let Some(position) = timeline.mouse_cursor(pos) {
+                grid.seek(position);
+            }
+            step_back.mouse_cursor(pos);
+            next.mouse_cursor(pos);
+            jump.mouse_cursor(pos);
+            play.mouse_cursor(pos);
+            random.mouse_cursor(pos);
+            decrease.mouse_cursor(pos);
+            increase.mouse_cursor(pos);
+            for button in &mut tool_buttons {
+                button.mouse_cursor(pos);
+            }
+            rule_select.mouse_cursor(pos);
+            topology_select.mouse_cursor(pos);
+            backend_select.mouse_cursor(pos);
+            threads_select.mouse_cursor(pos);
+            tracking_select.mouse_cursor(pos);
+            library.mouse_cursor(pos);
+        }
+
+        if let Event::Input(Input::FileDrag(FileDrag::Drop(path)), _) = &e {
+            if grid.load(path).is_some() {
+                rule_select.set_rule(grid.universe.rule());
+            }
+        }
+
+        if let Some(scroll) = e.mouse_scroll_args() {
+            jump.scroll(scroll);
+            grid.scroll(scroll);
+        }
+
+        if let Some(text) = e.text_args() {
+            rule_select.text(&text);
+        }
+
+        if let Some(button) = e.release_args() {
+            if let Button::Keyboard(Key::LCtrl | Key::RCtrl) = button {
+                ctrl = false;
+            }
+            timeline.release(&button);
+            grid.release(button);
+        }
+
+        if let Some(button) = e.press_args() {
+            if let Button::Keyboard(Key::LCtrl | Key::RCtrl) = button {
+                ctrl = true;
+            }
+            if ctrl && !rule_select.editing {
+                match button {
+                    Button::Keyboard(Key::Z) => grid.undo(),
+                    Button::Keyboard(Key::Y) => grid.redo(),
+                    Button::Keyboard(Key::C) => grid.copy(),
+                    Button::Keyboard(Key::X) => grid.cut(),
+                    Button::Keyboard(Key::V) => grid.start_paste(),
+                    Button::Keyboard(Key::R) => grid.copy_seed(),
+                    _ => {}
+                }
+            }
+            // Keys typed into the rule field or held with ctrl shouldn't also drive the grid
+            let editing = rule_select.editing;
+            if let Some(rule) = rule_select.press(&button) {
+                grid.set_rule(rule);
+                if grid.universe.rule() != rule {
+                    rule_select.set_rule(grid.universe.rule());
+                }
+            }
+            if let Some(index) = topology_select.press(&button) {
+                grid.set_topology(Topology::ALL[index]);
+            }
+            if let Some(index) = backend_select.press(&button) {
+                grid.set_backend(Backend::ALL[index]);
+                rule_select.set_rule(grid.universe.rule());
+                jump.set_max(grid.backend.max_step_log());
+            }
+            if let Some(index) = threads_select.press(&button) {
+                grid.set_parallel(index == 1);
+            }
+            if let Some(index) = tracking_select.press(&button) {
+                grid.set_track_activity(index == 1);
+            }
+            if !((editing || ctrl) && matches!(button, Button::Keyboard(_))) {
+                grid.press(button, mouse_pos);
+            }
+            if let Some(entry) = library.press(&button) {
+                grid.stamp(entry.pattern());
+            }
+            if let Some(position) = timeline.press(&button, mouse_pos) {
+                grid.seek(position);
+            }
+
+            if step_back.is_pressed(&button) {
+                grid.step_back();
+            }
+
+            if next.is_pressed(&button) {
+                grid.calc_next();
+            }
+
+            if jump.is_pressed(&button) {
+                grid.jump(jump.step_log);
+            }
+
+            if play.is_pressed(&button) {
+                playing = !playing;
+            }
+
+            if random.is_pressed(&button) {
+                grid.randomize(true);
+            }
+
+            if let Some(symmetry) = random.press_symmetry(&button, grid.soup.symmetry) {
+                grid.soup.symmetry = symmetry;
+                grid.message = Some(grid.soup_settings());
+            }
+
+            if increase.is_pressed(&button) {
+                speed.increase();
+            }
+
+            if decrease.is_pressed(&button) {
+                speed.decrease();
+            }
+
+            if let Some(pressed) = tool_buttons.iter_mut().position(|b| b.is_pressed(&button)) {
+                grid.tool = tool_buttons[pressed].tool;
+                for button in &mut tool_buttons {
+                    button.selected = button.tool == grid.tool;
+                }
+            }
+        }
+
+        if playing
+            && SystemTime::now()
+                .duration_since(last_tick)
+                .expect("Time went backwards")
+                .as_millis()
+                > (1000 / speed.speed as u128)
+        {
+            last_tick = SystemTime::now();
+            grid.calc_next();
+        }
+    }
+}
+
+// Removes `name value` from the arguments and parses the value, exiting when it's
+// missing or invalid
+fn take_option<T: FromStr>(args: &mut Vec<String>, name: &str, expected: &str) -> Option<T> {
+    let index = args.iter().position(|arg| arg == name)?;
+    let Some(value) = args.get(index + 1).and_then(|arg| arg.parse().ok()) else {
+        eprintln!("{} needs {}", name, expected);
+        std::process::exit(2);
+    };
+    args.drain(index..index + 2);
+    Some(value)
+}
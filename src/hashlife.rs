@@ -0,0 +1,773 @@
+use std::{collections::HashMap, mem::size_of};
+
+use crate::{
+    pattern::macrocell::{self, Macrocell},
+    rule::Rule,
+};
+
+type NodeId = u32;
+
+const DEAD: NodeId = 0;
+const ALIVE: NodeId = 1;
+// Deep enough that coordinates never overflow i64
+const MAX_LEVEL: u8 = 62;
+
+#[derive(Clone, Copy)]
+struct Node {
+    level: u8,
+    // nw, ne, sw, se, unused for single cells
+    children: [NodeId; 4],
+    population: u64,
+    // Centre of the node advanced by the current step size
+    result: Option<NodeId>,
+}
+
+// Grid of blocks being counted by density
+struct Blocks {
+    x: i64,
+    y: i64,
+    cols: usize,
+    rows: usize,
+    level: u32,
+    counts: Vec<u64>,
+}
+
+// Memoised quadtree universe (Gosper's HashLife). The root node of level L covers
+// [-2^(L-1), 2^(L-1)) on both axes. As with the sparse universe, births on zero
+// neighbours are never applied.
+pub struct HashLife {
+    nodes: Vec<Node>,
+    index: HashMap<[NodeId; 4], NodeId>,
+    // Empty node for each level
+    empty: Vec<NodeId>,
+    root: NodeId,
+    rule: Rule,
+    step_log: u8,
+    memory_budget: usize,
+}
+
+impl HashLife {
+    // Largest jump a single step can make, the root can't grow past MAX_LEVEL
+    pub const MAX_STEP_LOG: u8 = MAX_LEVEL - 3;
+
+    // Unreachable nodes are garbage collected between steps once the node store
+    // grows past memory_budget bytes
+    pub fn new(memory_budget: usize) -> Self {
+        let mut hashlife = Self {
+            nodes: Vec::new(),
+            index: HashMap::new(),
+            empty: Vec::new(),
+            root: DEAD,
+            rule: Rule::default(),
+            step_log: 0,
+            memory_budget,
+        };
+        hashlife.reset();
+        hashlife
+    }
+
+    fn reset(&mut self) {
+        self.nodes.clear();
+        self.index.clear();
+        self.empty.clear();
+        for population in 0..2 {
+            self.nodes.push(Node {
+                level: 0,
+                children: [DEAD; 4],
+                population,
+                result: None,
+            });
+        }
+        self.empty.push(DEAD);
+        self.root = self.empty_node(3);
+    }
+
+    // Drops every node, the rule and step size are kept
+    pub fn clear(&mut self) {
+        self.reset();
+    }
+
+    pub fn rule(&self) -> Rule {
+        self.rule
+    }
+
+    pub fn set_rule(&mut self, rule: Rule) {
+        if rule != self.rule {
+            self.rule = rule;
+            self.clear_results(0);
+        }
+    }
+
+    // Each call to step advances 2^step_log generations
+    pub fn set_step_log(&mut self, step_log: u8) {
+        let step_log = step_log.min(Self::MAX_STEP_LOG);
+        if step_log != self.step_log {
+            // Nodes small enough to run at full speed under both step sizes keep their results
+            self.clear_results(self.step_log.min(step_log) + 2);
+            self.step_log = step_log;
+        }
+    }
+
+    pub fn memory_used(&self) -> usize {
+        self.nodes.len() * NODE_BYTES
+    }
+
+    pub fn population(&self) -> u64 {
+        self.nodes[self.root as usize].population
+    }
+
+    fn level(&self, id: NodeId) -> u8 {
+        self.nodes[id as usize].level
+    }
+
+    fn children(&self, id: NodeId) -> [NodeId; 4] {
+        self.nodes[id as usize].children
+    }
+
+    fn empty_node(&mut self, level: u8) -> NodeId {
+        while self.empty.len() <= level as usize {
+            let child = *self.empty.last().expect("Missing empty cell");
+            let node = self.join([child; 4]);
+            self.empty.push(node);
+        }
+        self.empty[level as usize]
+    }
+
+    fn join(&mut self, children: [NodeId; 4]) -> NodeId {
+        if let Some(&id) = self.index.get(&children) {
+            return id;
+        }
+        let id = self.nodes.len() as NodeId;
+        self.nodes.push(Node {
+            level: self.level(children[0]) + 1,
+            children,
+            population: children
+                .iter()
+                .map(|&c| self.nodes[c as usize].population)
+                .sum(),
+            result: None,
+        });
+        self.index.insert(children, id);
+        id
+    }
+
+    fn half_size(&self) -> i64 {
+        1 << (self.level(self.root) - 1)
+    }
+
+    fn contains(&self, x: i64, y: i64) -> bool {
+        let half = self.half_size();
+        x >= -half && x < half && y >= -half && y < half
+    }
+
+    // Grows the root by one level, keeping the pattern centred
+    fn expand(&mut self) {
+        self.root = self.embed(self.root);
+    }
+
+    // A node one level up with the given node in its centre
+    fn embed(&mut self, node: NodeId) -> NodeId {
+        let level = self.level(node);
+        let e = self.empty_node(level - 1);
+        let [nw, ne, sw, se] = self.children(node);
+        let nw = self.join([e, e, e, nw]);
+        let ne = self.join([e, e, ne, e]);
+        let sw = self.join([e, sw, e, e]);
+        let se = self.join([se, e, e, e]);
+        self.join([nw, ne, sw, se])
+    }
+
+    // Cells alive in either node, shared subtrees are only merged once
+    fn union(
+        &mut self,
+        a: NodeId,
+        b: NodeId,
+        merged: &mut HashMap<(NodeId, NodeId), NodeId>,
+    ) -> NodeId {
+        if a == b || self.nodes[b as usize].population == 0 {
+            return a;
+        }
+        if self.nodes[a as usize].population == 0 {
+            return b;
+        }
+        if self.level(a) == 0 {
+            return ALIVE;
+        }
+        if let Some(&node) = merged.get(&(a, b)) {
+            return node;
+        }
+        let (a_children, b_children) = (self.children(a), self.children(b));
+        let mut children = [DEAD; 4];
+        for (child, (&a, &b)) in children.iter_mut().zip(a_children.iter().zip(&b_children)) {
+            *child = self.union(a, b, merged);
+        }
+        let node = self.join(children);
+        merged.insert((a, b), node);
+        node
+    }
+
+    // Adds the macrocell's cells with its root centred on the origin. The tree is
+    // rebuilt node for node, so huge patterns never get flattened into cells.
+    pub fn insert_macrocell(&mut self, macrocell: &Macrocell) {
+        let Some(root_level) = macrocell.nodes.last().map(macrocell::Node::level) else {
+            return;
+        };
+        let mut ids = Vec::with_capacity(macrocell.nodes.len());
+        for node in &macrocell.nodes {
+            let id = match *node {
+                macrocell::Node::Leaf(rows) => self.leaf(&rows, 0, 0, 3),
+                macrocell::Node::Branch { level: 1, children } => {
+                    self.join(children.map(|state| if state == 0 { DEAD } else { ALIVE }))
+                }
+                macrocell::Node::Branch { level, children } => {
+                    let mut ids_of = [DEAD; 4];
+                    for (id, &child) in ids_of.iter_mut().zip(&children) {
+                        *id = match child {
+                            0 => self.empty_node(level - 1),
+                            child => ids[child - 1],
+                        };
+                    }
+                    self.join(ids_of)
+                }
+            };
+            ids.push(id);
+        }
+        let mut tree = *ids.last().expect("Missing macrocell root");
+        while self.level(self.root) < root_level {
+            self.expand();
+        }
+        while self.level(tree) < self.level(self.root) {
+            tree = self.embed(tree);
+        }
+        self.root = self.union(self.root, tree, &mut HashMap::new());
+    }
+
+    fn leaf(&mut self, rows: &[u8; 8], x: usize, y: usize, level: u8) -> NodeId {
+        if level == 0 {
+            return if rows[y] & (1 << x) != 0 { ALIVE } else { DEAD };
+        }
+        let half = 1 << (level - 1);
+        let nw = self.leaf(rows, x, y, level - 1);
+        let ne = self.leaf(rows, x + half, y, level - 1);
+        let sw = self.leaf(rows, x, y + half, level - 1);
+        let se = self.leaf(rows, x + half, y + half, level - 1);
+        self.join([nw, ne, sw, se])
+    }
+
+    // Writes the quadtree out as it is, sharing nodes just like the universe does
+    pub fn to_macrocell(&self) -> Macrocell {
+        let mut macrocell = Macrocell {
+            rule: Some(self.rule),
+            ..Macrocell::default()
+        };
+        if self.population() > 0 {
+            self.export_node(self.root, &mut macrocell, &mut HashMap::new());
+        }
+        macrocell
+    }
+
+    fn export_node(
+        &self,
+        node: NodeId,
+        macrocell: &mut Macrocell,
+        exported: &mut HashMap<NodeId, usize>,
+    ) -> usize {
+        if self.nodes[node as usize].population == 0 {
+            return 0;
+        }
+        if let Some(&index) = exported.get(&node) {
+            return index;
+        }
+        let level = self.level(node);
+        // Stepping can leave the root smaller than a leaf, it is padded out to one
+        // with the same centre
+        let exported_node = if level <= 3 {
+            let offset = 4 - (1 << level) / 2;
+            let mut cells = Vec::new();
+            self.collect_cells(node, offset, offset, &mut cells);
+            let mut rows = [0; 8];
+            for (x, y) in cells {
+                rows[y as usize] |= 1 << x;
+            }
+            macrocell::Node::Leaf(rows)
+        } else {
+            let mut children = [0; 4];
+            for (index, &child) in children.iter_mut().zip(&self.children(node)) {
+                *index = self.export_node(child, macrocell, exported);
+            }
+            macrocell::Node::Branch { level, children }
+        };
+        macrocell.nodes.push(exported_node);
+        exported.insert(node, macrocell.nodes.len());
+        macrocell.nodes.len()
+    }
+
+    pub fn get(&self, x: i64, y: i64) -> bool {
+        if !self.contains(x, y) {
+            return false;
+        }
+        let half = self.half_size();
+        let (mut x, mut y) = (x + half, y + half);
+        let mut node = self.root;
+        while self.level(node) > 0 {
+            if self.nodes[node as usize].population == 0 {
+                return false;
+            }
+            let half = 1 << (self.level(node) - 1);
+            let quadrant = (y >= half) as usize * 2 + (x >= half) as usize;
+            x %= half;
+            y %= half;
+            node = self.children(node)[quadrant];
+        }
+        node == ALIVE
+    }
+
+    pub fn set(&mut self, x: i64, y: i64, alive: bool) {
+        if !alive && !self.contains(x, y) {
+            return;
+        }
+        while !self.contains(x, y) && self.level(self.root) < MAX_LEVEL {
+            self.expand();
+        }
+        let half = self.half_size();
+        self.root = self.set_in(self.root, x + half, y + half, alive);
+    }
+
+    fn set_in(&mut self, node: NodeId, x: i64, y: i64, alive: bool) -> NodeId {
+        let level = self.level(node);
+        if level == 0 {
+            return if alive { ALIVE } else { DEAD };
+        }
+        let half = 1 << (level - 1);
+        let quadrant = (y >= half) as usize * 2 + (x >= half) as usize;
+        let mut children = self.children(node);
+        children[quadrant] = self.set_in(children[quadrant], x % half, y % half, alive);
+        self.join(children)
+    }
+
+    pub fn live_cells(&self) -> Vec<(i64, i64)> {
+        let mut cells = Vec::with_capacity(self.population() as usize);
+        let half = self.half_size();
+        self.collect_cells(self.root, -half, -half, &mut cells);
+        cells
+    }
+
+    fn collect_cells(&self, node: NodeId, x: i64, y: i64, cells: &mut Vec<(i64, i64)>) {
+        let n = &self.nodes[node as usize];
+        if n.population == 0 {
+            return;
+        }
+        if n.level == 0 {
+            cells.push((x, y));
+            return;
+        }
+        let half = 1 << (n.level - 1);
+        let [nw, ne, sw, se] = n.children;
+        self.collect_cells(nw, x, y, cells);
+        self.collect_cells(ne, x + half, y, cells);
+        self.collect_cells(sw, x, y + half, cells);
+        self.collect_cells(se, x + half, y + half, cells);
+    }
+
+    // Live cells in each block of a cols x rows grid of 2^level sided blocks with its
+    // top left at (x, y). Nodes inside a single block are counted from their
+    // population without being descended, so blocks aligned to multiples of 2^level
+    // stay cheap however many cells they hold.
+    pub fn density(&self, x: i64, y: i64, cols: usize, rows: usize, level: u32) -> Vec<u64> {
+        let mut blocks = Blocks {
+            x,
+            y,
+            cols,
+            rows,
+            level,
+            counts: vec![0; cols * rows],
+        };
+        let half = self.half_size();
+        self.count_blocks(self.root, -half, -half, &mut blocks);
+        blocks.counts
+    }
+
+    fn count_blocks(&self, node: NodeId, x: i64, y: i64, blocks: &mut Blocks) {
+        let n = &self.nodes[node as usize];
+        if n.population == 0 {
+            return;
+        }
+        let size = 1i64 << n.level;
+        let block = 1i64 << blocks.level;
+        let [left, top] = [
+            (x - blocks.x).div_euclid(block),
+            (y - blocks.y).div_euclid(block),
+        ];
+        let right = (x + size - 1 - blocks.x).div_euclid(block);
+        let bottom = (y + size - 1 - blocks.y).div_euclid(block);
+        if right < 0 || bottom < 0 || left >= blocks.cols as i64 || top >= blocks.rows as i64 {
+            return;
+        }
+        if left == right && top == bottom {
+            blocks.counts[top as usize * blocks.cols + left as usize] += n.population;
+            return;
+        }
+        let half = size / 2;
+        let [nw, ne, sw, se] = n.children;
+        self.count_blocks(nw, x, y, blocks);
+        self.count_blocks(ne, x + half, y, blocks);
+        self.count_blocks(sw, x, y + half, blocks);
+        self.count_blocks(se, x + half, y + half, blocks);
+    }
+
+    // Smallest and largest live coordinates. Each distinct node is only measured once,
+    // so this stays cheap for huge but repetitive patterns.
+    pub fn bounding_box(&self) -> Option<([i64; 2], [i64; 2])> {
+        let half = self.half_size();
+        let [min_x, min_y, max_x, max_y] = self.node_bounds(self.root, &mut HashMap::new())?;
+        Some(([min_x - half, min_y - half], [max_x - half, max_y - half]))
+    }
+
+    // Bounds relative to the node's top left corner as [min_x, min_y, max_x, max_y]
+    fn node_bounds(
+        &self,
+        node: NodeId,
+        measured: &mut HashMap<NodeId, Option<[i64; 4]>>,
+    ) -> Option<[i64; 4]> {
+        let n = &self.nodes[node as usize];
+        if n.population == 0 {
+            return None;
+        }
+        if n.level == 0 {
+            return Some([0; 4]);
+        }
+        if let Some(&bounds) = measured.get(&node) {
+            return bounds;
+        }
+        let half = 1 << (n.level - 1);
+        let mut bounds: Option<[i64; 4]> = None;
+        for (quadrant, &child) in n.children.iter().enumerate() {
+            let Some([min_x, min_y, max_x, max_y]) = self.node_bounds(child, measured) else {
+                continue;
+            };
+            let (dx, dy) = ((quadrant % 2) as i64 * half, (quadrant / 2) as i64 * half);
+            let child = [min_x + dx, min_y + dy, max_x + dx, max_y + dy];
+            bounds = Some(match bounds {
+                Some(b) => [
+                    b[0].min(child[0]),
+                    b[1].min(child[1]),
+                    b[2].max(child[2]),
+                    b[3].max(child[3]),
+                ],
+                None => child,
+            });
+        }
+        measured.insert(node, bounds);
+        bounds
+    }
+
+    // Advances the universe by 2^step_log generations
+    pub fn step(&mut self) {
+        // The pattern has to sit in the middle sixteenth so nothing can escape the result
+        while self.level(self.root) < self.step_log + 3 || !self.centred() {
+            if self.level(self.root) >= MAX_LEVEL {
+                break;
+            }
+            self.expand();
+        }
+        self.root = self.advance(self.root);
+        self.collect_if_needed();
+    }
+
+    fn centred(&self) -> bool {
+        let [nw, ne, sw, se] = self.children(self.root);
+        let inner = self.children(self.children(nw)[3])[3] as usize;
+        let inner_ne = self.children(self.children(ne)[2])[2] as usize;
+        let inner_sw = self.children(self.children(sw)[1])[1] as usize;
+        let inner_se = self.children(self.children(se)[0])[0] as usize;
+        self.nodes[inner].population
+            + self.nodes[inner_ne].population
+            + self.nodes[inner_sw].population
+            + self.nodes[inner_se].population
+            == self.population()
+    }
+
+    fn centre(&mut self, node: NodeId) -> NodeId {
+        let [nw, ne, sw, se] = self.children(node);
+        self.join([
+            self.children(nw)[3],
+            self.children(ne)[2],
+            self.children(sw)[1],
+            self.children(se)[0],
+        ])
+    }
+
+    // Returns the centre of a level L node advanced by 2^min(step_log, L - 2) generations
+    fn advance(&mut self, node: NodeId) -> NodeId {
+        let n = self.nodes[node as usize];
+        if let Some(result) = n.result {
+            return result;
+        }
+        let result = if n.population == 0 {
+            self.empty_node(n.level - 1)
+        } else if n.level == 2 {
+            self.advance_base(node)
+        } else {
+            let [nw, ne, sw, se] = n.children;
+            let [_, nw_ne, nw_sw, nw_se] = self.children(nw);
+            let [ne_nw, _, ne_sw, ne_se] = self.children(ne);
+            let [sw_nw, sw_ne, _, sw_se] = self.children(sw);
+            let [se_nw, se_ne, se_sw, _] = self.children(se);
+            let n01 = self.join([nw_ne, ne_nw, nw_se, ne_sw]);
+            let n10 = self.join([nw_sw, nw_se, sw_nw, sw_ne]);
+            let n11 = self.join([nw_se, ne_sw, sw_ne, se_nw]);
+            let n12 = self.join([ne_sw, ne_se, se_nw, se_ne]);
+            let n21 = self.join([sw_ne, se_nw, sw_se, se_sw]);
+            let sub = [nw, n01, ne, n10, n11, n12, sw, n21, se];
+            // At full speed both halves of the jump are advanced, otherwise only the second
+            let full_speed = self.step_log + 2 >= n.level;
+            let mut r = [DEAD; 9];
+            for (slot, &s) in r.iter_mut().zip(sub.iter()) {
+                *slot = if full_speed {
+                    self.advance(s)
+                } else {
+                    self.centre(s)
+                };
+            }
+            let c_nw = self.join([r[0], r[1], r[3], r[4]]);
+            let c_ne = self.join([r[1], r[2], r[4], r[5]]);
+            let c_sw = self.join([r[3], r[4], r[6], r[7]]);
+            let c_se = self.join([r[4], r[5], r[7], r[8]]);
+            let children = [
+                self.advance(c_nw),
+                self.advance(c_ne),
+                self.advance(c_sw),
+                self.advance(c_se),
+            ];
+            self.join(children)
+        };
+        self.nodes[node as usize].result = Some(result);
+        result
+    }
+
+    // One generation of the centre 2x2 of a 4x4 node
+    fn advance_base(&mut self, node: NodeId) -> NodeId {
+        let mut cells = [[false; 4]; 4];
+        for (quadrant, &child) in self.children(node).iter().enumerate() {
+            for (i, &cell) in self.children(child).iter().enumerate() {
+                let x = (quadrant % 2) * 2 + i % 2;
+                let y = (quadrant / 2) * 2 + i / 2;
+                cells[y][x] = cell == ALIVE;
+            }
+        }
+        let mut centre = [DEAD; 4];
+        for (i, cell) in centre.iter_mut().enumerate() {
+            let (cx, cy) = (1 + i % 2, 1 + i / 2);
+            let mut neighbours = 0;
+            for (y, row) in cells.iter().enumerate().skip(cy - 1).take(3) {
+                for (x, &alive) in row.iter().enumerate().skip(cx - 1).take(3) {
+                    if (x, y) != (cx, cy) && alive {
+                        neighbours += 1;
+                    }
+                }
+            }
+            let alive = cells[cy][cx];
+            if (alive || neighbours > 0) && self.rule.next(alive, neighbours) {
+                *cell = ALIVE;
+            }
+        }
+        self.join(centre)
+    }
+
+    fn clear_results(&mut self, keep_level: u8) {
+        for node in self.nodes.iter_mut().filter(|node| node.level > keep_level) {
+            node.result = None;
+        }
+    }
+
+    fn collect_if_needed(&mut self) {
+        if self.memory_used() > self.memory_budget {
+            self.collect();
+        }
+    }
+
+    // Drops every node not reachable from the root, keeping cached results whose
+    // targets survive
+    fn collect(&mut self) {
+        let mut marked = vec![false; self.nodes.len()];
+        marked[DEAD as usize] = true;
+        marked[ALIVE as usize] = true;
+        let mut stack = vec![self.root];
+        stack.extend(self.empty.iter().copied());
+        while let Some(id) = stack.pop() {
+            if marked[id as usize] {
+                continue;
+            }
+            marked[id as usize] = true;
+            let node = &self.nodes[id as usize];
+            if node.level > 0 {
+                stack.extend(node.children.iter().copied());
+            }
+        }
+
+        let mut remap = vec![NodeId::MAX; self.nodes.len()];
+        let mut nodes = Vec::with_capacity(marked.iter().filter(|&&m| m).count());
+        // Children always have lower ids than their parents, so one pass keeps the order valid
+        for (id, node) in self.nodes.iter().enumerate() {
+            if marked[id] {
+                remap[id] = nodes.len() as NodeId;
+                nodes.push(*node);
+            }
+        }
+        self.index.clear();
+        for (id, node) in nodes.iter_mut().enumerate() {
+            if node.level > 0 {
+                for child in node.children.iter_mut() {
+                    *child = remap[*child as usize];
+                }
+                self.index.insert(node.children, id as NodeId);
+            }
+            node.result = node
+                .result
+                .map(|r| remap[r as usize])
+                .filter(|&r| r != NodeId::MAX);
+        }
+        self.nodes = nodes;
+        self.root = remap[self.root as usize];
+        for empty in self.empty.iter_mut() {
+            *empty = remap[*empty as usize];
+        }
+    }
+}
+
+// Rough cost of one node including its hash table entry
+const NODE_BYTES: usize = size_of::<Node>() + 2 * size_of::<[NodeId; 4]>();
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::Engine;
+    use crate::soup::Soup;
+    use crate::sparse::SparseBoard;
+
+    fn sorted(mut cells: Vec<(i64, i64)>) -> Vec<(i64, i64)> {
+        cells.sort_unstable();
+        cells
+    }
+
+    // Writes the universe out as macrocell text and loads it into a new one
+    fn reload(hashlife: &HashLife) -> HashLife {
+        let text = macrocell::write(&hashlife.to_macrocell());
+        let macrocell = macrocell::read(&text).expect("Unable to read exported macrocell");
+        let mut reloaded = HashLife::new(1 << 20);
+        reloaded.insert_macrocell(&macrocell);
+        reloaded
+    }
+
+    #[test]
+    fn exports_after_stepping() {
+        let mut hashlife = HashLife::new(1 << 20);
+        // A block around the origin fits the smallest root, which a step then halves
+        for (x, y) in [(-1, -1), (0, -1), (-1, 0), (0, 0)] {
+            hashlife.set(x, y, true);
+        }
+        hashlife.step();
+        assert!(hashlife.level(hashlife.root) < 3);
+        let reloaded = reload(&hashlife);
+        assert_eq!(sorted(reloaded.live_cells()), sorted(hashlife.live_cells()));
+        assert_eq!(reloaded.rule(), hashlife.rule());
+    }
+
+    #[test]
+    fn exports_at_every_step_size() {
+        for step_log in [0, 1, 4, 8] {
+            let mut hashlife = HashLife::new(1 << 20);
+            hashlife.set_step_log(step_log);
+            // R-pentomino, offset so it isn't centred on the origin
+            for (x, y) in [(6, -3), (7, -3), (5, -2), (6, -2), (6, -1)] {
+                hashlife.set(x, y, true);
+            }
+            for _ in 0..3 {
+                hashlife.step();
+                let reloaded = reload(&hashlife);
+                assert_eq!(sorted(reloaded.live_cells()), sorted(hashlife.live_cells()));
+            }
+        }
+    }
+
+    #[test]
+    fn exports_empty_universe() {
+        let mut hashlife = HashLife::new(1 << 20);
+        hashlife.step();
+        assert_eq!(reload(&hashlife).population(), 0);
+    }
+
+    fn soup(rule: Rule, seed: u64) -> (HashLife, SparseBoard) {
+        let mut hashlife = HashLife::new(1 << 20);
+        let mut sparse = SparseBoard::new();
+        hashlife.set_rule(rule);
+        sparse.set_rule(rule);
+        let soup = Soup {
+            seed,
+            density: 40,
+            ..Soup::default()
+        };
+        for (i, alive) in soup.cells(16, 16).into_iter().enumerate() {
+            let [x, y] = [(i % 16) as i64 - 5, (i / 16) as i64 - 9];
+            hashlife.set(x, y, alive);
+            sparse.set(x, y, alive);
+        }
+        (hashlife, sparse)
+    }
+
+    fn assert_same(hashlife: &HashLife, sparse: &SparseBoard, what: &str, generation: u64) {
+        assert_eq!(
+            sorted(hashlife.live_cells()),
+            sorted(sparse.live_cells().collect()),
+            "{} differs at generation {}",
+            what,
+            generation
+        );
+    }
+
+    #[test]
+    fn step_matches_sparse() {
+        for rule in ["B3/S23", "B36/S23", "B3678/S34678"] {
+            let rule: Rule = rule.parse().expect("Unable to parse rule");
+            let (mut hashlife, mut sparse) = soup(rule, 3);
+            for generation in 1..=50 {
+                Engine::step(&mut hashlife);
+                sparse.step();
+                assert_same(&hashlife, &sparse, &rule.to_string(), generation);
+            }
+        }
+    }
+
+    #[test]
+    fn step_n_matches_sparse() {
+        let (mut hashlife, mut sparse) = soup(Rule::default(), 5);
+        let mut generation = 0;
+        // Counts with several set bits, so step_n changes step size within a call
+        for generations in [1, 2, 7, 13, 64, 100] {
+            Engine::step_n(&mut hashlife, generations);
+            for _ in 0..generations {
+                sparse.step();
+            }
+            generation += generations;
+            assert_same(&hashlife, &sparse, "step_n", generation);
+        }
+        // Single steps after jumps still use the right step size
+        for _ in 0..5 {
+            Engine::step(&mut hashlife);
+            sparse.step();
+            generation += 1;
+            assert_same(&hashlife, &sparse, "step", generation);
+        }
+    }
+
+    #[test]
+    fn step_n_splits_counts_past_the_largest_jump() {
+        let mut hashlife = HashLife::new(1 << 20);
+        let block = [(0, 0), (1, 0), (0, 1), (1, 1)];
+        for (x, y) in block {
+            hashlife.set(x, y, true);
+        }
+        Engine::step_n(&mut hashlife, u64::MAX);
+        assert_eq!(sorted(hashlife.live_cells()), sorted(block.to_vec()));
+    }
+}
@@ -0,0 +1,246 @@
+use std::{
+    fs,
+    io::{self, Write},
+    path::Path,
+};
+
+use gol_rs::{
+    backend::{parse_size, Backend, HASHLIFE_MEMORY_BUDGET},
+    pattern::{macrocell, Format, Pattern},
+    rule::Rule,
+    topology::Topology,
+};
+
+pub const USAGE: &str = "\
+usage: gol-rs run <pattern> [options]
+
+Runs a pattern without opening a window and writes the final state.
+
+options:
+  -h, --help               show this message
+  -n, --generations <N>    generations to run (default 0)
+  -r, --rule <RULE>        rule to use instead of the pattern's own, e.g. B36/S23
+  -s, --size <COLSxROWS>   run on a bounded board of this size, pattern centred
+  -t, --topology <NAME>    bounded, torus, horizontal-cylinder, vertical-cylinder,
+                           klein-bottle or cross-surface (needs --size)
+  -b, --backend <NAME>     dense, bit-packed, sparse or hashlife
+      --hashlife-memory <MIB>
+                           node cache size of the hashlife backend (default 256)
+  -o, --output <FILE>      final state, '-' for stdout (default)
+  -f, --format <FORMAT>    rle, cells, life105, life106 or mc (default from the
+                           output extension, otherwise rle)
+  -p, --population <FILE>  population of every generation, '-' for stdout";
+
+struct Options {
+    pattern: String,
+    generations: u64,
+    rule: Option<Rule>,
+    size: Option<[usize; 2]>,
+    topology: Topology,
+    backend: Option<Backend>,
+    hashlife_budget: usize,
+    output: String,
+    format: Option<Format>,
+    population: Option<String>,
+}
+
+// Entry point of `gol-rs run`, args are everything after the subcommand
+pub fn run(args: &[String]) -> Result<(), String> {
+    if args.iter().any(|arg| arg == "-h" || arg == "--help") {
+        println!("{}", USAGE);
+        return Ok(());
+    }
+    let options = parse_options(args)?;
+    let backend = match (options.backend, options.size) {
+        (Some(backend), _) => backend,
+        (None, Some(_)) => Backend::Packed,
+        (None, None) => Backend::Sparse,
+    };
+    let bounded = backend.bounded();
+    if options.topology != Topology::Bounded && !bounded {
+        return Err("a topology needs a bounded backend".to_string());
+    }
+    if options.size.is_some() && !bounded {
+        return Err("--size needs a bounded backend".to_string());
+    }
+
+    let text = fs::read_to_string(&options.pattern)
+        .map_err(|error| format!("unable to read {}: {}", options.pattern, error))?;
+    let format = Format::detect(Path::new(&options.pattern), &text);
+    let read_error = |error| format!("{}: {}", options.pattern, error);
+    // Macrocells go straight into an unbounded engine (as a tree for HashLife),
+    // everything else is placed cell by cell
+    let (pattern, tree) = if format == Format::Macrocell && !bounded {
+        let tree = macrocell::read(&text).map_err(read_error)?;
+        let pattern = Pattern {
+            rule: tree.rule,
+            name: tree.name.clone(),
+            ..Pattern::default()
+        };
+        (pattern, Some(tree))
+    } else {
+        (format.read(&text).map_err(read_error)?, None)
+    };
+
+    // Unbounded engines never apply B0, so they would quietly run a different rule
+    let rule = options.rule.or(pattern.rule).unwrap_or_default();
+    if rule.birth(0) && !bounded {
+        return Err(format!("{} needs a bounded backend", rule));
+    }
+    let [cols, rows] = match (bounded, options.size) {
+        (true, Some(size)) => size,
+        (true, None) => return Err(format!("the {} backend needs --size", backend.name())),
+        (false, _) => [0, 0],
+    };
+
+    // Centre the pattern on a bounded board, or on the origin of an unbounded one
+    let [x, y] = [
+        (cols as i64 - pattern.width as i64) / 2,
+        (rows as i64 - pattern.height as i64) / 2,
+    ];
+    let mut universe = backend.create(cols, rows, options.hashlife_budget);
+    universe.set_topology(options.topology);
+    for &(cx, cy) in &pattern.cells {
+        universe.set(x + cx, y + cy, true);
+    }
+    if let Some(tree) = &tree {
+        universe.insert_macrocell(tree);
+    }
+    universe.set_rule(rule);
+
+    if let Some(path) = options.population.as_deref() {
+        let mut out = open(path)?;
+        for generation in 0..=options.generations {
+            if generation > 0 {
+                universe.step();
+            }
+            writeln!(out, "{} {}", generation, universe.population())
+                .map_err(|error| format!("unable to write population: {}", error))?;
+        }
+    } else {
+        // Without a population log HashLife can cover the run in a few jumps
+        universe.step_n(options.generations);
+    }
+
+    let format = options
+        .format
+        .or_else(|| Format::from_path(Path::new(&options.output)))
+        .unwrap_or(Format::Rle);
+    // HashLife writes its tree as is rather than listing every cell
+    let text = if format == Format::Macrocell {
+        let mut result = universe.to_macrocell();
+        result.rule = Some(universe.rule());
+        result.name = pattern.name;
+        macrocell::write(&result)
+    } else {
+        let mut result = Pattern::from_cells(universe.live_cells());
+        result.rule = Some(universe.rule());
+        result.name = pattern.name;
+        format.write(&result)
+    };
+    open(&options.output)?
+        .write_all(text.as_bytes())
+        .map_err(|error| format!("unable to write {}: {}", options.output, error))
+}
+
+fn parse_options(args: &[String]) -> Result<Options, String> {
+    let mut options = Options {
+        pattern: String::new(),
+        generations: 0,
+        rule: None,
+        size: None,
+        topology: Topology::Bounded,
+        backend: None,
+        hashlife_budget: HASHLIFE_MEMORY_BUDGET,
+        output: "-".to_string(),
+        format: None,
+        population: None,
+    };
+    let mut pattern = None;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if !arg.starts_with('-') || arg == "-" {
+            if pattern.replace(arg.clone()).is_some() {
+                return Err(format!("unexpected argument '{}'", arg));
+            }
+            continue;
+        }
+        let mut value = || args.next().ok_or_else(|| format!("{} needs a value", arg));
+        match arg.as_str() {
+            "-n" | "--generations" => {
+                let value = value()?;
+                options.generations = value
+                    .parse()
+                    .map_err(|_| format!("'{}' is not a generation count", value))?;
+            }
+            "-r" | "--rule" => {
+                let value = value()?;
+                options.rule = Some(
+                    value
+                        .parse()
+                        .map_err(|error| format!("invalid rule '{}': {}", value, error))?,
+                );
+            }
+            "-s" | "--size" => {
+                let value = value()?;
+                options.size =
+                    Some(parse_size(value).ok_or_else(|| format!("'{}' is not COLSxROWS", value))?);
+            }
+            "-t" | "--topology" => options.topology = parse_topology(value()?)?,
+            "-b" | "--backend" => {
+                let value = value()?;
+                options.backend = Some(
+                    Backend::from_name(value)
+                        .ok_or_else(|| format!("unknown backend '{}'", value))?,
+                );
+            }
+            "--hashlife-memory" => {
+                let value = value()?;
+                let mib: usize = value
+                    .parse()
+                    .map_err(|_| format!("'{}' is not a size in MiB", value))?;
+                options.hashlife_budget = mib * 1024 * 1024;
+            }
+            "-o" | "--output" => options.output = value()?.clone(),
+            "-f" | "--format" => options.format = Some(parse_format(value()?)?),
+            "-p" | "--population" => options.population = Some(value()?.clone()),
+            _ => return Err(format!("unknown option '{}'", arg)),
+        }
+    }
+    options.pattern = pattern.ok_or("missing pattern file")?;
+    Ok(options)
+}
+
+fn parse_topology(name: &str) -> Result<Topology, String> {
+    match name {
+        "bounded" => Ok(Topology::Bounded),
+        "torus" => Ok(Topology::Torus),
+        "horizontal-cylinder" => Ok(Topology::HorizontalCylinder),
+        "vertical-cylinder" => Ok(Topology::VerticalCylinder),
+        "klein-bottle" => Ok(Topology::KleinBottle),
+        "cross-surface" => Ok(Topology::CrossSurface),
+        _ => Err(format!("unknown topology '{}'", name)),
+    }
+}
+
+fn parse_format(name: &str) -> Result<Format, String> {
+    match name {
+        "rle" => Ok(Format::Rle),
+        "cells" | "plaintext" => Ok(Format::Plaintext),
+        "life105" => Ok(Format::Life105),
+        "life106" | "lif" => Ok(Format::Life106),
+        "mc" | "macrocell" => Ok(Format::Macrocell),
+        _ => Err(format!("unknown format '{}'", name)),
+    }
+}
+
+// '-' is stdout, anything else a file that gets created or truncated
+fn open(path: &str) -> Result<Box<dyn Write>, String> {
+    if path == "-" {
+        Ok(Box::new(io::stdout().lock()))
+    } else {
+        let file = fs::File::create(path)
+            .map_err(|error| format!("unable to create {}: {}", path, error))?;
+        Ok(Box::new(io::BufWriter::new(file)))
+    }
+}
@@ -0,0 +1,302 @@
+use std::{collections::VecDeque, mem::size_of};
+
+use crate::engine::Engine;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Change {
+    // Anything done by hand, such as toggling, randomizing or loading a pattern
+    Edit,
+    // A step or jump of this many generations
+    Generations(u64),
+}
+
+// Only the cells that flipped are kept, so a long run of a small pattern stays cheap
+struct Entry {
+    change: Change,
+    born: Vec<(i64, i64)>,
+    died: Vec<(i64, i64)>,
+    // Live cells once the change is made
+    population: u64,
+}
+
+impl Entry {
+    fn memory_used(&self) -> usize {
+        (self.born.len() + self.died.len()) * CELL_BYTES + size_of::<Entry>()
+    }
+}
+
+// Bounded undo/redo history of board states. The oldest entries are dropped once
+// the deltas on both stacks take more than memory_budget bytes.
+pub struct History {
+    undo: VecDeque<Entry>,
+    redo: Vec<Entry>,
+    // Live cells before the oldest entry
+    start_population: u64,
+    memory_used: usize,
+    memory_budget: usize,
+}
+
+impl History {
+    pub fn new(memory_budget: usize) -> Self {
+        Self {
+            undo: VecDeque::new(),
+            redo: Vec::new(),
+            start_population: 0,
+            memory_used: 0,
+            memory_budget,
+        }
+    }
+
+    pub fn memory_used(&self) -> usize {
+        self.memory_used
+    }
+
+    // Largest number of live cells worth diffing, beyond it a delta could never be kept
+    pub fn max_cells(&self) -> usize {
+        self.memory_budget / CELL_BYTES
+    }
+
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+        self.memory_used = 0;
+    }
+
+    // Number of entries that can be undone, the current place in the timeline
+    pub fn position(&self) -> usize {
+        self.undo.len()
+    }
+
+    pub fn redoable(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    // Generations since the oldest entry and population for every position from
+    // the oldest state to the newest redoable one
+    pub fn timeline(&self) -> Vec<(u64, u64)> {
+        let mut generation = 0;
+        let mut timeline = vec![(0, self.start_population)];
+        for entry in self.undo.iter().chain(self.redo.iter().rev()) {
+            if let Change::Generations(generations) = entry.change {
+                generation += generations;
+            }
+            timeline.push((generation, entry.population));
+        }
+        timeline
+    }
+
+    // The change that undo would revert
+    pub fn last(&self) -> Option<Change> {
+        self.undo.back().map(|entry| entry.change)
+    }
+
+    // Records the difference between two sorted lists of live cells, dropping
+    // anything that could be redone
+    pub fn record(&mut self, change: Change, before: &[(i64, i64)], after: &[(i64, i64)]) {
+        let mut entry = diff(change, before, after);
+        entry.population = after.len() as u64;
+        if entry.born.is_empty() && entry.died.is_empty() && change == Change::Edit {
+            return;
+        }
+        self.memory_used -= self.redo.iter().map(Entry::memory_used).sum::<usize>();
+        self.redo.clear();
+        if self.undo.is_empty() {
+            self.start_population = before.len() as u64;
+        }
+        self.memory_used += entry.memory_used();
+        self.undo.push_back(entry);
+        while self.memory_used > self.memory_budget {
+            let Some(oldest) = self.undo.pop_front() else {
+                break;
+            };
+            self.memory_used -= oldest.memory_used();
+            self.start_population = oldest.population;
+        }
+    }
+
+    pub fn undo(&mut self, engine: &mut dyn Engine) -> Option<Change> {
+        let entry = self.undo.pop_back()?;
+        apply(engine, &entry.died, &entry.born);
+        let change = entry.change;
+        self.redo.push(entry);
+        Some(change)
+    }
+
+    pub fn redo(&mut self, engine: &mut dyn Engine) -> Option<Change> {
+        let entry = self.redo.pop()?;
+        apply(engine, &entry.born, &entry.died);
+        let change = entry.change;
+        self.undo.push_back(entry);
+        Some(change)
+    }
+}
+
+fn apply(engine: &mut dyn Engine, alive: &[(i64, i64)], dead: &[(i64, i64)]) {
+    for &(x, y) in dead {
+        engine.set(x, y, false);
+    }
+    for &(x, y) in alive {
+        engine.set(x, y, true);
+    }
+}
+
+// Born cells are only in after, dead ones only in before
+fn diff(change: Change, before: &[(i64, i64)], after: &[(i64, i64)]) -> Entry {
+    let (mut born, mut died) = (Vec::new(), Vec::new());
+    let (mut i, mut j) = (0, 0);
+    while i < before.len() || j < after.len() {
+        match (before.get(i), after.get(j)) {
+            (Some(a), Some(b)) if a == b => {
+                i += 1;
+                j += 1;
+            }
+            (Some(&a), Some(&b)) if a < b => {
+                died.push(a);
+                i += 1;
+            }
+            (Some(&a), None) => {
+                died.push(a);
+                i += 1;
+            }
+            (_, Some(&b)) => {
+                born.push(b);
+                j += 1;
+            }
+            (None, None) => unreachable!(),
+        }
+    }
+    Entry {
+        change,
+        born,
+        died,
+        population: 0,
+    }
+}
+
+const CELL_BYTES: usize = size_of::<(i64, i64)>();
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sparse::SparseBoard;
+
+    fn cells(engine: &dyn Engine) -> Vec<(i64, i64)> {
+        let mut cells = engine.live_cells();
+        cells.sort_unstable();
+        cells
+    }
+
+    // Runs an operation on the board, recording it like the app does
+    fn record(
+        history: &mut History,
+        board: &mut SparseBoard,
+        change: Change,
+        operation: impl FnOnce(&mut SparseBoard),
+    ) {
+        let before = cells(board);
+        operation(board);
+        history.record(change, &before, &cells(board));
+    }
+
+    fn blinker(board: &mut SparseBoard) {
+        for x in 0..3 {
+            board.set(x, 0, true);
+        }
+    }
+
+    #[test]
+    fn diffs_sorted_cells() {
+        let entry = diff(
+            Change::Edit,
+            &[(0, 0), (1, 1), (2, 2)],
+            &[(1, 1), (2, 2), (3, 3), (4, 4)],
+        );
+        assert_eq!(entry.born, vec![(3, 3), (4, 4)]);
+        assert_eq!(entry.died, vec![(0, 0)]);
+        let entry = diff(Change::Edit, &[(0, 0), (5, 5)], &[]);
+        assert!(entry.born.is_empty());
+        assert_eq!(entry.died, vec![(0, 0), (5, 5)]);
+    }
+
+    #[test]
+    fn undoes_and_redoes() {
+        let mut history = History::new(1 << 20);
+        let mut board = SparseBoard::new();
+        record(&mut history, &mut board, Change::Edit, blinker);
+        let edited = cells(&board);
+        record(&mut history, &mut board, Change::Generations(1), |board| {
+            board.step()
+        });
+        let stepped = cells(&board);
+        assert_ne!(edited, stepped);
+        assert_eq!(history.position(), 2);
+        assert_eq!(history.last(), Some(Change::Generations(1)));
+
+        assert_eq!(history.undo(&mut board), Some(Change::Generations(1)));
+        assert_eq!(cells(&board), edited);
+        assert_eq!(history.undo(&mut board), Some(Change::Edit));
+        assert!(cells(&board).is_empty());
+        assert_eq!(history.undo(&mut board), None);
+        assert!(history.redoable());
+
+        assert_eq!(history.redo(&mut board), Some(Change::Edit));
+        assert_eq!(history.redo(&mut board), Some(Change::Generations(1)));
+        assert_eq!(cells(&board), stepped);
+        assert_eq!(history.redo(&mut board), None);
+        assert_eq!(history.timeline(), vec![(0, 0), (0, 3), (1, 3)]);
+    }
+
+    #[test]
+    fn new_changes_drop_redo() {
+        let mut history = History::new(1 << 20);
+        let mut board = SparseBoard::new();
+        record(&mut history, &mut board, Change::Edit, blinker);
+        history.undo(&mut board);
+        record(&mut history, &mut board, Change::Edit, |board| {
+            board.set(5, 5, true)
+        });
+        assert!(!history.redoable());
+        assert_eq!(history.position(), 1);
+        assert_eq!(history.timeline(), vec![(0, 0), (0, 1)]);
+    }
+
+    #[test]
+    fn skips_edits_that_change_nothing() {
+        let mut history = History::new(1 << 20);
+        let mut board = SparseBoard::new();
+        record(&mut history, &mut board, Change::Edit, |_| {});
+        assert_eq!(history.position(), 0);
+        // Generations still count towards the timeline when nothing moves
+        record(&mut history, &mut board, Change::Generations(4), |_| {});
+        assert_eq!(history.position(), 1);
+    }
+
+    #[test]
+    fn drops_oldest_over_budget() {
+        // Room for a few single cell edits
+        let budget = 3 * (CELL_BYTES + size_of::<Entry>());
+        let mut history = History::new(budget);
+        let mut board = SparseBoard::new();
+        for x in 0..5 {
+            record(&mut history, &mut board, Change::Edit, |board| {
+                board.set(x, 0, true)
+            });
+            assert!(history.memory_used() <= budget);
+        }
+        assert_eq!(history.position(), 3);
+        // The timeline starts from the oldest state still kept
+        assert_eq!(history.timeline(), vec![(0, 2), (0, 3), (0, 4), (0, 5)]);
+        while history.undo(&mut board).is_some() {}
+        assert_eq!(cells(&board), vec![(0, 0), (1, 0)]);
+
+        history.clear();
+        assert_eq!(history.memory_used(), 0);
+        assert_eq!(history.position(), 0);
+    }
+
+    #[test]
+    fn max_cells_fits_the_budget() {
+        let history = History::new(1024);
+        assert_eq!(history.max_cells() * CELL_BYTES, 1024);
+    }
+}
@@ -0,0 +1,17 @@
+// Windowing-free simulation core, the Piston app in gui.rs is one consumer of it
+pub mod backend;
+pub mod bitboard;
+pub mod board;
+pub mod clipboard;
+pub mod engine;
+pub mod hashlife;
+pub mod history;
+pub mod library;
+pub mod pattern;
+pub mod rule;
+pub mod shape;
+pub mod soup;
+pub mod sparse;
+#[cfg(test)]
+mod testing;
+pub mod topology;
@@ -0,0 +1,213 @@
+use std::fmt;
+
+use crate::pattern::{rle, Pattern};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Category {
+    StillLife,
+    Oscillator,
+    Spaceship,
+    Gun,
+    Methuselah,
+    Puffer,
+}
+
+impl Category {
+    pub const ALL: [Category; 6] = [
+        Category::StillLife,
+        Category::Oscillator,
+        Category::Spaceship,
+        Category::Gun,
+        Category::Methuselah,
+        Category::Puffer,
+    ];
+}
+
+impl fmt::Display for Category {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Category::StillLife => write!(f, "Still lifes"),
+            Category::Oscillator => write!(f, "Oscillators"),
+            Category::Spaceship => write!(f, "Spaceships"),
+            Category::Gun => write!(f, "Guns"),
+            Category::Methuselah => write!(f, "Methuselahs"),
+            Category::Puffer => write!(f, "Puffers"),
+        }
+    }
+}
+
+pub struct Entry {
+    pub name: &'static str,
+    pub category: Category,
+    pub description: &'static str,
+    rle: &'static str,
+}
+
+impl Entry {
+    pub fn pattern(&self) -> Pattern {
+        let mut pattern = rle::read(self.rle).expect("Built in pattern is invalid");
+        pattern.name = Some(self.name.to_string());
+        pattern
+    }
+}
+
+pub fn in_category(category: Category) -> impl Iterator<Item = &'static Entry> {
+    PATTERNS
+        .iter()
+        .filter(move |entry| entry.category == category)
+}
+
+// Built in catalogue of well known Conway's Life patterns
+pub static PATTERNS: &[Entry] = &[
+    Entry {
+        name: "Block",
+        category: Category::StillLife,
+        description: "The smallest and most common still life.",
+        rle: "x = 2, y = 2\n2o$2o!",
+    },
+    Entry {
+        name: "Beehive",
+        category: Category::StillLife,
+        description: "The second most common still life, often left behind by soups.",
+        rle: "x = 4, y = 3\nb2o$o2bo$b2o!",
+    },
+    Entry {
+        name: "Loaf",
+        category: Category::StillLife,
+        description: "A seven cell still life, third most common after the block and beehive.",
+        rle: "x = 4, y = 4\nb2o$o2bo$bobo$2bo!",
+    },
+    Entry {
+        name: "Boat",
+        category: Category::StillLife,
+        description: "The only five cell still life.",
+        rle: "x = 3, y = 3\n2o$obo$bo!",
+    },
+    Entry {
+        name: "Tub",
+        category: Category::StillLife,
+        description: "Four cells around an empty centre.",
+        rle: "x = 3, y = 3\nbo$obo$bo!",
+    },
+    Entry {
+        name: "Blinker",
+        category: Category::Oscillator,
+        description: "The smallest oscillator, period 2.",
+        rle: "x = 3, y = 1\n3o!",
+    },
+    Entry {
+        name: "Toad",
+        category: Category::Oscillator,
+        description: "Period 2 oscillator made of two offset rows of three.",
+        rle: "x = 4, y = 2\nb3o$3o!",
+    },
+    Entry {
+        name: "Beacon",
+        category: Category::Oscillator,
+        description: "Two diagonal blocks whose inner corners blink, period 2.",
+        rle: "x = 4, y = 4\n2o$2o$2b2o$2b2o!",
+    },
+    Entry {
+        name: "Pulsar",
+        category: Category::Oscillator,
+        description: "The most common period 3 oscillator, with four fold symmetry.",
+        rle: "x = 13, y = 13\n2b3o3b3o2$o4bobo4bo$o4bobo4bo$o4bobo4bo$2b3o3b3o2$2b3o3b3o$o4bobo4bo$o4bobo4bo$o4bobo4bo2$2b3o3b3o!",
+    },
+    Entry {
+        name: "Pentadecathlon",
+        category: Category::Oscillator,
+        description: "Period 15 oscillator that starts as a row of ten cells.",
+        rle: "x = 10, y = 3\n2bo4bo$2ob4ob2o$2bo4bo!",
+    },
+    Entry {
+        name: "Glider",
+        category: Category::Spaceship,
+        description: "The smallest spaceship, moving diagonally one cell every four generations.",
+        rle: "x = 3, y = 3\nbo$2bo$3o!",
+    },
+    Entry {
+        name: "Lightweight spaceship",
+        category: Category::Spaceship,
+        description: "The smallest orthogonal spaceship, moving at c/2.",
+        rle: "x = 5, y = 4\nbo2bo$o$o3bo$4o!",
+    },
+    Entry {
+        name: "Middleweight spaceship",
+        category: Category::Spaceship,
+        description: "Orthogonal c/2 spaceship, one cell longer than the lightweight.",
+        rle: "x = 6, y = 5\n3bo$bo3bo$o$o4bo$5o!",
+    },
+    Entry {
+        name: "Heavyweight spaceship",
+        category: Category::Spaceship,
+        description: "The largest of the three standard c/2 spaceships.",
+        rle: "x = 7, y = 5\n3b2o$bo4bo$o$o5bo$6o!",
+    },
+    Entry {
+        name: "Gosper glider gun",
+        category: Category::Gun,
+        description: "The first known gun, firing a glider every 30 generations.",
+        rle: "x = 36, y = 9\n24bo$22bobo$12b2o6b2o12b2o$11bo3bo4b2o12b2o$2o8bo5bo3b2o$2o8bo3bob2o4bobo$10bo5bo7bo$11bo3bo$12b2o!",
+    },
+    Entry {
+        name: "Simkin glider gun",
+        category: Category::Gun,
+        description: "A period 120 glider gun built from two interacting B-heptominoes.",
+        rle: "x = 33, y = 21\n2o5b2o$2o5b2o2$4b2o$4b2o5$22b2ob2o$21bo5bo$21bo6bo2b2o$21b3o3bo3b2o$26bo4$20b2o$20bo$21b3o$23bo!",
+    },
+    Entry {
+        name: "R-pentomino",
+        category: Category::Methuselah,
+        description: "Five cells that take 1103 generations to settle, releasing six gliders.",
+        rle: "x = 3, y = 3\nb2o$2o$bo!",
+    },
+    Entry {
+        name: "Diehard",
+        category: Category::Methuselah,
+        description: "Vanishes completely after 130 generations.",
+        rle: "x = 8, y = 3\n6bo$2o$bo3b3o!",
+    },
+    Entry {
+        name: "Acorn",
+        category: Category::Methuselah,
+        description: "Seven cells that take 5206 generations to stabilise.",
+        rle: "x = 7, y = 3\nbo$3bo$2o2b3o!",
+    },
+    Entry {
+        name: "Ten cell infinite growth",
+        category: Category::Puffer,
+        description: "The smallest pattern that grows forever, it becomes a block-laying switch engine.",
+        rle: "x = 8, y = 6\n6bo$4bob2o$4bobo$4bo$2bo$obo!",
+    },
+    Entry {
+        name: "One cell thick infinite growth",
+        category: Category::Puffer,
+        description: "A single row 39 cells wide that turns into two block-laying switch engines.",
+        rle: "x = 39, y = 1\n8ob5o3b3o6b7ob5o!",
+    },
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_pattern_parses() {
+        for entry in PATTERNS {
+            let pattern = rle::read(entry.rle)
+                .unwrap_or_else(|error| panic!("{} is invalid: {}", entry.name, error));
+            assert!(!pattern.cells.is_empty(), "{} is empty", entry.name);
+        }
+    }
+
+    #[test]
+    fn names_are_unique() {
+        for (i, entry) in PATTERNS.iter().enumerate() {
+            assert!(
+                PATTERNS[..i].iter().all(|other| other.name != entry.name),
+                "{} is listed twice",
+                entry.name
+            );
+        }
+    }
+}
@@ -0,0 +1,296 @@
+use std::{error::Error, fmt, path::Path};
+
+use crate::{pattern::macrocell::Macrocell, rule::Rule};
+
+pub mod life;
+pub mod macrocell;
+pub mod plaintext;
+pub mod rle;
+
+// A pattern with its live cells relative to the top left of its bounding box
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Pattern {
+    pub width: usize,
+    pub height: usize,
+    pub cells: Vec<(i64, i64)>,
+    pub rule: Option<Rule>,
+    pub name: Option<String>,
+    pub comments: Vec<String>,
+}
+
+impl Pattern {
+    // Shifts the cells so the bounding box starts at (0, 0)
+    pub fn from_cells(cells: Vec<(i64, i64)>) -> Self {
+        let Some(min_x) = cells.iter().map(|&(x, _)| x).min() else {
+            return Self::default();
+        };
+        let min_y = cells.iter().map(|&(_, y)| y).min().unwrap_or(0);
+        let max_x = cells.iter().map(|&(x, _)| x).max().unwrap_or(0);
+        let max_y = cells.iter().map(|&(_, y)| y).max().unwrap_or(0);
+        let mut cells: Vec<(i64, i64)> = cells
+            .into_iter()
+            .map(|(x, y)| (x - min_x, y - min_y))
+            .collect();
+        cells.sort_by_key(|&(x, y)| (y, x));
+        Self {
+            width: (max_x - min_x + 1) as usize,
+            height: (max_y - min_y + 1) as usize,
+            cells,
+            ..Self::default()
+        }
+    }
+
+    // A quarter turn clockwise, the bounding box stays at (0, 0)
+    pub fn rotate_clockwise(&self) -> Self {
+        let height = self.height as i64;
+        self.transform(self.height, self.width, |x, y| (height - 1 - y, x))
+    }
+
+    // Mirrored left to right
+    pub fn flip_horizontal(&self) -> Self {
+        let width = self.width as i64;
+        self.transform(self.width, self.height, |x, y| (width - 1 - x, y))
+    }
+
+    // Mirrored top to bottom
+    pub fn flip_vertical(&self) -> Self {
+        let height = self.height as i64;
+        self.transform(self.width, self.height, |x, y| (x, height - 1 - y))
+    }
+
+    fn transform(&self, width: usize, height: usize, map: impl Fn(i64, i64) -> (i64, i64)) -> Self {
+        let mut cells: Vec<(i64, i64)> = self.cells.iter().map(|&(x, y)| map(x, y)).collect();
+        cells.sort_by_key(|&(x, y)| (y, x));
+        Self {
+            width,
+            height,
+            cells,
+            rule: self.rule,
+            name: self.name.clone(),
+            comments: self.comments.clone(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    Rle,
+    Plaintext,
+    Life105,
+    Life106,
+    Macrocell,
+}
+
+impl Format {
+    pub fn next(self) -> Self {
+        match self {
+            Format::Rle => Format::Plaintext,
+            Format::Plaintext => Format::Life105,
+            Format::Life105 => Format::Life106,
+            Format::Life106 => Format::Macrocell,
+            Format::Macrocell => Format::Rle,
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            Format::Rle => "rle",
+            Format::Plaintext => "cells",
+            Format::Life105 | Format::Life106 => "lif",
+            Format::Macrocell => "mc",
+        }
+    }
+
+    // .lif and .life are shared by both Life versions, so only the header can tell them apart
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str() {
+            "rle" => Some(Format::Rle),
+            "cells" | "txt" => Some(Format::Plaintext),
+            "lif" | "life" => Some(Format::Life106),
+            "mc" => Some(Format::Macrocell),
+            _ => None,
+        }
+    }
+
+    // Guesses the format from the first line that isn't an RLE style comment
+    pub fn sniff(text: &str) -> Option<Self> {
+        let first = text.lines().map(str::trim).find(|line| !line.is_empty())?;
+        if first.starts_with(life::HEADER_105) {
+            return Some(Format::Life105);
+        }
+        if first.starts_with(life::HEADER_106) {
+            return Some(Format::Life106);
+        }
+        if first.starts_with(macrocell::HEADER) {
+            return Some(Format::Macrocell);
+        }
+        if first.starts_with('!') {
+            return Some(Format::Plaintext);
+        }
+        let line = text
+            .lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty() && !line.starts_with('#'))?;
+        if line.starts_with('x') && line[1..].trim_start().starts_with('=') {
+            Some(Format::Rle)
+        } else if line.chars().all(|c| matches!(c, '.' | 'O')) {
+            Some(Format::Plaintext)
+        } else {
+            None
+        }
+    }
+
+    // The content wins over the extension, and RLE is assumed when neither helps
+    pub fn detect(path: &Path, text: &str) -> Self {
+        Self::sniff(text)
+            .or_else(|| Self::from_path(path))
+            .unwrap_or(Format::Rle)
+    }
+
+    pub fn read(self, text: &str) -> Result<Pattern, ParseError> {
+        match self {
+            Format::Rle => rle::read(text),
+            Format::Plaintext => plaintext::read(text),
+            Format::Life105 => life::read_105(text),
+            Format::Life106 => life::read_106(text),
+            Format::Macrocell => macrocell::read(text).map(|macrocell| macrocell.to_pattern()),
+        }
+    }
+
+    pub fn write(self, pattern: &Pattern) -> String {
+        match self {
+            Format::Rle => rle::write(pattern),
+            Format::Plaintext => plaintext::write(pattern),
+            Format::Life105 => life::write_105(pattern),
+            Format::Life106 => life::write_106(pattern),
+            Format::Macrocell => macrocell::write(&Macrocell::from_pattern(pattern)),
+        }
+    }
+}
+
+impl fmt::Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Format::Rle => "RLE",
+            Format::Plaintext => "Plaintext",
+            Format::Life105 => "Life 1.05",
+            Format::Life106 => "Life 1.06",
+            Format::Macrocell => "Macrocell",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+// Position of a problem in a pattern file, both 1-based
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl ParseError {
+    pub fn new(line: usize, column: usize, message: impl Into<String>) -> Self {
+        Self {
+            line,
+            column,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: {}",
+            self.line, self.column, self.message
+        )
+    }
+}
+
+impl Error for ParseError {}
+
+// Whitespace separated tokens with their 1-based columns
+fn tokens(line: &str) -> impl Iterator<Item = (usize, &str)> {
+    line.split(|c: char| c.is_whitespace())
+        .scan(0, |offset, token| {
+            let column = *offset + 1;
+            *offset += token.len() + 1;
+            Some((column, token))
+        })
+        .filter(|(_, token)| !token.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_by_extension() {
+        let format = |path: &str| Format::from_path(Path::new(path));
+        assert_eq!(format("glider.rle"), Some(Format::Rle));
+        assert_eq!(format("GLIDER.RLE"), Some(Format::Rle));
+        assert_eq!(format("glider.cells"), Some(Format::Plaintext));
+        assert_eq!(format("glider.txt"), Some(Format::Plaintext));
+        assert_eq!(format("glider.lif"), Some(Format::Life106));
+        assert_eq!(format("glider.life"), Some(Format::Life106));
+        assert_eq!(format("glider.mc"), Some(Format::Macrocell));
+        assert_eq!(format("glider.png"), None);
+        assert_eq!(format("glider"), None);
+    }
+
+    #[test]
+    fn detects_by_content() {
+        assert_eq!(Format::sniff("#Life 1.05\n#N\n*\n"), Some(Format::Life105));
+        assert_eq!(Format::sniff("\n#Life 1.06\n0 0\n"), Some(Format::Life106));
+        assert_eq!(
+            Format::sniff("[M2] (golly 4.0)\n#R B3/S23\n"),
+            Some(Format::Macrocell)
+        );
+        assert_eq!(
+            Format::sniff("!Name: Glider\n.O.\n"),
+            Some(Format::Plaintext)
+        );
+        assert_eq!(Format::sniff(".O.\n..O\nOOO\n"), Some(Format::Plaintext));
+        assert_eq!(
+            Format::sniff("#N Glider\n#C comment\nx = 3, y = 3\nbo$2bo$3o!"),
+            Some(Format::Rle)
+        );
+        assert_eq!(Format::sniff("x=3,y=3\nbo$2bo$3o!"), Some(Format::Rle));
+        assert_eq!(Format::sniff("hello"), None);
+        assert_eq!(Format::sniff(""), None);
+    }
+
+    #[test]
+    fn content_wins_over_extension() {
+        let life_105 = "#Life 1.05\n*\n";
+        assert_eq!(
+            Format::detect(Path::new("a.lif"), life_105),
+            Format::Life105
+        );
+        assert_eq!(
+            Format::detect(Path::new("a.rle"), ".O\n"),
+            Format::Plaintext
+        );
+        assert_eq!(Format::detect(Path::new("a.lif"), "hello"), Format::Life106);
+        assert_eq!(Format::detect(Path::new("a"), "hello"), Format::Rle);
+    }
+
+    #[test]
+    fn every_format_round_trips() {
+        let mut pattern = rle::read("x = 5, y = 4\nbo$2bobo$3o$4bo!").unwrap();
+        pattern.name = Some("Glider and friends".to_string());
+        let mut format = Format::Rle;
+        loop {
+            let read = format.read(&format.write(&pattern)).unwrap();
+            assert_eq!(read.cells, pattern.cells, "{}", format);
+            assert_eq!((read.width, read.height), (5, 4), "{}", format);
+            format = format.next();
+            if format == Format::Rle {
+                break;
+            }
+        }
+    }
+}
@@ -0,0 +1,259 @@
+use std::fmt::Write;
+
+use crate::{
+    pattern::{tokens, ParseError, Pattern},
+    rule::Rule,
+};
+
+pub const HEADER_105: &str = "#Life 1.05";
+pub const HEADER_106: &str = "#Life 1.06";
+
+// Life 1.05 limits lines to 80 characters, so wider patterns are split into blocks
+const BLOCK_WIDTH: usize = 80;
+
+// Life 1.06 is one "x y" pair per live cell, in any order and relative to any origin
+pub fn read_106(text: &str) -> Result<Pattern, ParseError> {
+    let mut cells = Vec::new();
+    for (line_index, line) in text.lines().enumerate() {
+        let line_number = line_index + 1;
+        if line.trim().is_empty() || line.trim_start().starts_with('#') {
+            continue;
+        }
+        let mut coordinates = [0; 2];
+        let mut tokens = tokens(line);
+        for (axis, coordinate) in ["x", "y"].into_iter().zip(&mut coordinates) {
+            let Some((column, token)) = tokens.next() else {
+                return Err(ParseError::new(
+                    line_number,
+                    line.len() + 1,
+                    format!("missing {} coordinate", axis),
+                ));
+            };
+            *coordinate = parse_coordinate(token, line_number, column)?;
+        }
+        if let Some((column, token)) = tokens.next() {
+            return Err(ParseError::new(
+                line_number,
+                column,
+                format!("unexpected '{}' after coordinates", token),
+            ));
+        }
+        cells.push((coordinates[0], coordinates[1]));
+    }
+    cells.sort_unstable();
+    cells.dedup();
+    Ok(Pattern::from_cells(cells))
+}
+
+pub fn write_106(pattern: &Pattern) -> String {
+    let mut text = String::new();
+    writeln!(text, "{}", HEADER_106).expect("Unable to write to string");
+    for &(x, y) in &pattern.cells {
+        writeln!(text, "{} {}", x, y).expect("Unable to write to string");
+    }
+    text
+}
+
+// Life 1.05 has "#P x y" blocks of '.' and '*' rows, with "#D" descriptions and
+// either "#N" for Conway's rule or "#R" with a survival/birth rule
+pub fn read_105(text: &str) -> Result<Pattern, ParseError> {
+    let mut cells = Vec::new();
+    let mut name = None;
+    let mut comments = Vec::new();
+    let mut rule = None;
+    let (mut block_x, mut y) = (0i64, 0i64);
+    for (line_index, line) in text.lines().enumerate() {
+        let line_number = line_index + 1;
+        if let Some(directive) = line.strip_prefix('#') {
+            let mut chars = directive.chars();
+            let kind = chars.next();
+            let value = chars.as_str().trim();
+            match kind {
+                Some('D') if name.is_none() && comments.is_empty() => {
+                    name = Some(value.to_string())
+                }
+                Some('D') => comments.push(value.to_string()),
+                Some('N') => rule = Some(Rule::conway()),
+                Some('R') => {
+                    rule = Some(value.parse::<Rule>().map_err(|error| {
+                        ParseError::new(line_number, 3, format!("invalid rule: {}", error))
+                    })?)
+                }
+                Some('P') => {
+                    let mut tokens = tokens(directive).skip(1);
+                    let mut position = [0; 2];
+                    for coordinate in &mut position {
+                        let Some((column, token)) = tokens.next() else {
+                            return Err(ParseError::new(
+                                line_number,
+                                line.len() + 1,
+                                "block needs an x and y position",
+                            ));
+                        };
+                        // Columns are relative to the directive, after the '#'
+                        *coordinate = parse_coordinate(token, line_number, column + 1)?;
+                    }
+                    [block_x, y] = position;
+                }
+                _ => {}
+            }
+            continue;
+        }
+        for (column_index, c) in line.trim_end().char_indices() {
+            match c {
+                '.' => {}
+                '*' => cells.push((block_x + column_index as i64, y)),
+                _ => {
+                    return Err(ParseError::new(
+                        line_number,
+                        column_index + 1,
+                        format!("unexpected '{}'", c),
+                    ))
+                }
+            }
+        }
+        y += 1;
+    }
+    cells.sort_unstable();
+    cells.dedup();
+    let mut pattern = Pattern::from_cells(cells);
+    pattern.name = name;
+    pattern.comments = comments;
+    pattern.rule = rule;
+    Ok(pattern)
+}
+
+pub fn write_105(pattern: &Pattern) -> String {
+    let mut text = String::new();
+    writeln!(text, "{}", HEADER_105).expect("Unable to write to string");
+    for line in pattern.name.iter().chain(&pattern.comments) {
+        writeln!(text, "#D {}", line).expect("Unable to write to string");
+    }
+    match pattern.rule {
+        Some(rule) if rule != Rule::conway() => {
+            let survival: String = (0..9)
+                .filter(|&n| rule.survival(n))
+                .map(|n| n.to_string())
+                .collect();
+            let birth: String = (0..9)
+                .filter(|&n| rule.birth(n))
+                .map(|n| n.to_string())
+                .collect();
+            writeln!(text, "#R {}/{}", survival, birth).expect("Unable to write to string");
+        }
+        _ => text.push_str("#N\n"),
+    }
+    // Blocks are positioned relative to the centre of the pattern, as is customary
+    let (centre_x, centre_y) = ((pattern.width / 2) as i64, (pattern.height / 2) as i64);
+    for block_x in (0..pattern.width).step_by(BLOCK_WIDTH) {
+        let block_width = BLOCK_WIDTH.min(pattern.width - block_x);
+        let mut rows = vec![vec!['.'; block_width]; pattern.height];
+        let mut empty = true;
+        for &(x, y) in &pattern.cells {
+            let x = x as usize;
+            if (block_x..block_x + block_width).contains(&x) {
+                rows[y as usize][x - block_x] = '*';
+                empty = false;
+            }
+        }
+        if empty {
+            continue;
+        }
+        writeln!(text, "#P {} {}", block_x as i64 - centre_x, -centre_y)
+            .expect("Unable to write to string");
+        for row in rows {
+            let row: String = row.into_iter().collect();
+            let row = row.trim_end_matches('.');
+            // An empty line would be ambiguous, so blank rows keep a single '.'
+            text.push_str(if row.is_empty() { "." } else { row });
+            text.push('\n');
+        }
+    }
+    text
+}
+
+fn parse_coordinate(token: &str, line_number: usize, column: usize) -> Result<i64, ParseError> {
+    token.parse::<i64>().map_err(|_| {
+        ParseError::new(
+            line_number,
+            column,
+            format!("'{}' is not a valid coordinate", token),
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_106_relative_to_any_origin() {
+        let pattern = read_106("#Life 1.06\n0 -1\n1 0\n-1 1\n0 1\n1 1\n1 1\n").unwrap();
+        assert_eq!((pattern.width, pattern.height), (3, 3));
+        assert_eq!(pattern.cells, vec![(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)]);
+    }
+
+    #[test]
+    fn round_trips_106() {
+        let pattern = read_106("#Life 1.06\n-5 -3\n10 -3\n-2 7\n").unwrap();
+        assert_eq!((pattern.width, pattern.height), (16, 11));
+        assert_eq!(read_106(&write_106(&pattern)).unwrap(), pattern);
+    }
+
+    #[test]
+    fn rejects_bad_106_lines() {
+        assert_eq!(
+            read_106("#Life 1.06\n1\n"),
+            Err(ParseError::new(2, 2, "missing y coordinate"))
+        );
+        assert_eq!(
+            read_106("#Life 1.06\n1 a\n"),
+            Err(ParseError::new(2, 3, "'a' is not a valid coordinate"))
+        );
+        assert_eq!(
+            read_106("#Life 1.06\n1 2 3\n"),
+            Err(ParseError::new(2, 5, "unexpected '3' after coordinates"))
+        );
+    }
+
+    #[test]
+    fn reads_105_blocks() {
+        let text = "#Life 1.05\n#D Two gliders\n#D far apart\n#N\n#P -1 -1\n.*\n..*\n***\n#P 97 -4\n*\n.*\n";
+        let pattern = read_105(text).unwrap();
+        assert_eq!(pattern.name.as_deref(), Some("Two gliders"));
+        assert_eq!(pattern.comments, vec!["far apart"]);
+        assert_eq!(pattern.rule, Some(Rule::conway()));
+        assert_eq!((pattern.width, pattern.height), (100, 6));
+        let mut cells = pattern.cells.clone();
+        cells.sort_by_key(|&(x, y)| (y, x));
+        assert_eq!(
+            cells,
+            vec![(98, 0), (99, 1), (1, 3), (2, 4), (0, 5), (1, 5), (2, 5)]
+        );
+    }
+
+    #[test]
+    fn round_trips_105() {
+        let text =
+            "#Life 1.05\n#D Two gliders\n#R 23/36\n#P -1 -1\n.*\n..*\n***\n#P 197 -40\n*\n.*\n";
+        let pattern = read_105(text).unwrap();
+        let written = write_105(&pattern);
+        // Wider than a block, so it is split again
+        assert!(written.matches("#P").count() >= 2);
+        assert!(written.lines().all(|line| line.len() <= BLOCK_WIDTH));
+        assert!(written.contains("#R 23/36"));
+        assert_eq!(read_105(&written).unwrap(), pattern);
+    }
+
+    #[test]
+    fn rejects_bad_105_blocks() {
+        assert_eq!(
+            read_105("#Life 1.05\n#P 1\n*\n"),
+            Err(ParseError::new(2, 5, "block needs an x and y position"))
+        );
+        assert_eq!(
+            read_105("#Life 1.05\n#P 0 0\n.O\n"),
+            Err(ParseError::new(3, 2, "unexpected 'O'"))
+        );
+    }
+}
@@ -0,0 +1,329 @@
+use std::{collections::HashMap, fmt::Write};
+
+use crate::{
+    pattern::{tokens, ParseError, Pattern},
+    rule::Rule,
+};
+
+pub const HEADER: &str = "[M2]";
+
+// Leaves are 8x8 blocks, and nothing deeper fits in i64 coordinates
+const LEAF_LEVEL: u8 = 3;
+const MAX_LEVEL: u8 = 62;
+
+// A node of size 2^level. Children are 1-based indices into Macrocell::nodes,
+// with 0 standing for an empty node of the right level. Level 1 nodes hold
+// cell states (0 or 1) instead of indices.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Node {
+    // One byte per row, bit 0 is the leftmost cell
+    Leaf([u8; 8]),
+    Branch { level: u8, children: [usize; 4] },
+}
+
+impl Node {
+    pub fn level(&self) -> u8 {
+        match self {
+            Node::Leaf(_) => LEAF_LEVEL,
+            Node::Branch { level, .. } => *level,
+        }
+    }
+}
+
+// Golly's quadtree format, where the last node is the root
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Macrocell {
+    pub rule: Option<Rule>,
+    pub name: Option<String>,
+    pub comments: Vec<String>,
+    pub nodes: Vec<Node>,
+}
+
+impl Macrocell {
+    pub fn from_pattern(pattern: &Pattern) -> Self {
+        let mut macrocell = Self {
+            rule: pattern.rule,
+            name: pattern.name.clone(),
+            comments: pattern.comments.clone(),
+            nodes: Vec::new(),
+        };
+        let mut level = LEAF_LEVEL;
+        while (1 << level) < pattern.width.max(pattern.height) {
+            level += 1;
+        }
+        let mut index = HashMap::new();
+        macrocell.build(&mut index, &pattern.cells, 0, 0, level);
+        macrocell
+    }
+
+    fn build(
+        &mut self,
+        index: &mut HashMap<Node, usize>,
+        cells: &[(i64, i64)],
+        x: i64,
+        y: i64,
+        level: u8,
+    ) -> usize {
+        if cells.is_empty() {
+            return 0;
+        }
+        let node = if level == LEAF_LEVEL {
+            let mut rows = [0; 8];
+            for &(cx, cy) in cells {
+                rows[(cy - y) as usize] |= 1 << (cx - x);
+            }
+            Node::Leaf(rows)
+        } else {
+            let half = 1 << (level - 1);
+            let mut children = [0; 4];
+            for (quadrant, child) in children.iter_mut().enumerate() {
+                let qx = x + (quadrant % 2) as i64 * half;
+                let qy = y + (quadrant / 2) as i64 * half;
+                let inside: Vec<(i64, i64)> = cells
+                    .iter()
+                    .copied()
+                    .filter(|&(cx, cy)| cx >= qx && cx < qx + half && cy >= qy && cy < qy + half)
+                    .collect();
+                *child = self.build(index, &inside, qx, qy, level - 1);
+            }
+            Node::Branch { level, children }
+        };
+        *index.entry(node).or_insert_with(|| {
+            self.nodes.push(node);
+            self.nodes.len()
+        })
+    }
+
+    pub fn level(&self) -> u8 {
+        self.nodes.last().map_or(0, Node::level)
+    }
+
+    // Every live cell, with the root's top left corner at (0, 0)
+    pub fn live_cells(&self) -> Vec<(i64, i64)> {
+        let mut cells = Vec::new();
+        if !self.nodes.is_empty() {
+            self.collect_cells(self.nodes.len(), 0, 0, &mut cells);
+        }
+        cells
+    }
+
+    // Flattens the tree into a pattern cropped to its live cells
+    pub fn to_pattern(&self) -> Pattern {
+        let mut pattern = Pattern::from_cells(self.live_cells());
+        pattern.rule = self.rule;
+        pattern.name = self.name.clone();
+        pattern.comments = self.comments.clone();
+        pattern
+    }
+
+    fn collect_cells(&self, index: usize, x: i64, y: i64, cells: &mut Vec<(i64, i64)>) {
+        if index == 0 {
+            return;
+        }
+        match self.nodes[index - 1] {
+            Node::Leaf(rows) => {
+                for (cy, row) in rows.iter().enumerate() {
+                    for cx in (0..8).filter(|&cx| row & (1 << cx) != 0) {
+                        cells.push((x + cx as i64, y + cy as i64));
+                    }
+                }
+            }
+            Node::Branch { level, children } => {
+                let half = 1 << (level - 1);
+                for (quadrant, &child) in children.iter().enumerate() {
+                    let qx = x + (quadrant % 2) as i64 * half;
+                    let qy = y + (quadrant / 2) as i64 * half;
+                    if level == 1 {
+                        if child != 0 {
+                            cells.push((qx, qy));
+                        }
+                    } else {
+                        self.collect_cells(child, qx, qy, cells);
+                    }
+                }
+            }
+        }
+    }
+}
+
+pub fn read(text: &str) -> Result<Macrocell, ParseError> {
+    let mut macrocell = Macrocell::default();
+    let mut lines = text.lines().enumerate();
+    if !lines
+        .next()
+        .is_some_and(|(_, line)| line.trim_start().starts_with(HEADER))
+    {
+        return Err(ParseError::new(
+            1,
+            1,
+            format!("expected '{}' header", HEADER),
+        ));
+    }
+    for (line_index, line) in lines {
+        let line_number = line_index + 1;
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+        if let Some(comment) = trimmed.strip_prefix('#') {
+            let mut chars = comment.chars();
+            let kind = chars.next();
+            let text = chars.as_str().trim().to_string();
+            match kind {
+                Some('R') => {
+                    macrocell.rule = Some(text.parse::<Rule>().map_err(|error| {
+                        ParseError::new(line_number, 4, format!("invalid rule: {}", error))
+                    })?)
+                }
+                Some('N') => macrocell.name = Some(text),
+                Some('C' | 'D') => macrocell.comments.push(text),
+                // Generation counts and anything else Golly adds aren't needed
+                _ => {}
+            }
+            continue;
+        }
+        let node = if trimmed.starts_with(|c: char| c.is_ascii_digit()) {
+            read_branch(&macrocell.nodes, line, line_number)?
+        } else {
+            read_leaf(line, line_number)?
+        };
+        macrocell.nodes.push(node);
+    }
+    Ok(macrocell)
+}
+
+// "level nw ne sw se"
+fn read_branch(nodes: &[Node], line: &str, line_number: usize) -> Result<Node, ParseError> {
+    let tokens: Vec<(usize, &str)> = tokens(line).collect();
+    if let Some(&(column, token)) = tokens.get(5) {
+        return Err(ParseError::new(
+            line_number,
+            column,
+            format!("unexpected '{}' after children", token),
+        ));
+    }
+    if tokens.len() < 5 {
+        return Err(ParseError::new(
+            line_number,
+            line.len() + 1,
+            "node needs a level and four children",
+        ));
+    }
+    let mut numbers = [0; 5];
+    for (number, &(column, token)) in numbers.iter_mut().zip(&tokens) {
+        *number = token.parse::<usize>().map_err(|_| {
+            ParseError::new(line_number, column, format!("'{}' is not a number", token))
+        })?;
+    }
+    let [level, children @ ..] = numbers;
+    let columns: Vec<usize> = tokens.iter().map(|&(column, _)| column).collect();
+    if level == 0 || level > MAX_LEVEL as usize {
+        return Err(ParseError::new(
+            line_number,
+            columns[0],
+            format!("level {} is out of range 1-{}", level, MAX_LEVEL),
+        ));
+    }
+    for (&child, &column) in children.iter().zip(&columns[1..]) {
+        if level == 1 {
+            if child > 1 {
+                return Err(ParseError::new(
+                    line_number,
+                    column,
+                    format!("cell state {} isn't 0 or 1", child),
+                ));
+            }
+        } else if child > nodes.len() {
+            return Err(ParseError::new(
+                line_number,
+                column,
+                format!("node {} isn't defined yet", child),
+            ));
+        } else if child > 0 && nodes[child - 1].level() as usize != level - 1 {
+            return Err(ParseError::new(
+                line_number,
+                column,
+                format!(
+                    "node {} has level {} but level {} is needed",
+                    child,
+                    nodes[child - 1].level(),
+                    level - 1
+                ),
+            ));
+        }
+    }
+    Ok(Node::Branch {
+        level: level as u8,
+        children,
+    })
+}
+
+// Rows of '.' and '*' ending in '$', with trailing dead cells and rows left out
+fn read_leaf(line: &str, line_number: usize) -> Result<Node, ParseError> {
+    let mut rows = [0u8; 8];
+    let (mut x, mut y) = (0, 0);
+    for (column_index, c) in line.trim_end().char_indices() {
+        let column = column_index + 1;
+        match c {
+            '.' | '*' if x >= 8 || y >= 8 => {
+                return Err(ParseError::new(
+                    line_number,
+                    column,
+                    "leaf is larger than 8x8",
+                ))
+            }
+            '.' => x += 1,
+            '*' => {
+                rows[y] |= 1 << x;
+                x += 1;
+            }
+            '$' => {
+                y += 1;
+                x = 0;
+            }
+            c if c.is_whitespace() => {}
+            _ => {
+                return Err(ParseError::new(
+                    line_number,
+                    column,
+                    format!("unexpected '{}'", c),
+                ))
+            }
+        }
+    }
+    Ok(Node::Leaf(rows))
+}
+
+pub fn write(macrocell: &Macrocell) -> String {
+    let mut text = String::new();
+    writeln!(text, "{}", HEADER).expect("Unable to write to string");
+    if let Some(rule) = macrocell.rule {
+        writeln!(text, "#R {}", rule).expect("Unable to write to string");
+    }
+    if let Some(name) = &macrocell.name {
+        writeln!(text, "#N {}", name).expect("Unable to write to string");
+    }
+    for comment in &macrocell.comments {
+        writeln!(text, "#C {}", comment).expect("Unable to write to string");
+    }
+    for node in &macrocell.nodes {
+        match node {
+            Node::Leaf(rows) => {
+                let last_row = rows.iter().rposition(|&row| row != 0).map_or(0, |y| y + 1);
+                for &row in &rows[..last_row] {
+                    for x in 0..8 - row.leading_zeros() {
+                        text.push(if row & (1 << x) != 0 { '*' } else { '.' });
+                    }
+                    text.push('$');
+                }
+            }
+            Node::Branch { level, children } => {
+                let [nw, ne, sw, se] = children;
+                write!(text, "{} {} {} {} {}", level, nw, ne, sw, se)
+                    .expect("Unable to write to string");
+            }
+        }
+        text.push('\n');
+    }
+    text
+}
@@ -0,0 +1,99 @@
+use std::fmt::Write;
+
+use crate::pattern::{ParseError, Pattern};
+
+// Reads the .cells format, '!' comment lines followed by rows of '.' and 'O'
+pub fn read(text: &str) -> Result<Pattern, ParseError> {
+    let mut pattern = Pattern::default();
+    let mut y = 0;
+    for (line_index, line) in text.lines().enumerate() {
+        let line_number = line_index + 1;
+        if let Some(comment) = line.strip_prefix('!') {
+            match comment.trim().strip_prefix("Name:") {
+                Some(name) => pattern.name = Some(name.trim().to_string()),
+                None => pattern.comments.push(comment.trim().to_string()),
+            }
+            continue;
+        }
+        let row = line.trim_end();
+        for (column_index, c) in row.char_indices() {
+            match c {
+                '.' => {}
+                // Some older files use '*' for live cells
+                'O' | '*' => pattern.cells.push((column_index as i64, y)),
+                _ => {
+                    return Err(ParseError::new(
+                        line_number,
+                        column_index + 1,
+                        format!("unexpected '{}'", c),
+                    ))
+                }
+            }
+        }
+        pattern.width = pattern.width.max(row.len());
+        y += 1;
+        // Blank lines are empty rows, but not when they trail the pattern
+        if !row.is_empty() {
+            pattern.height = y as usize;
+        }
+    }
+    Ok(pattern)
+}
+
+pub fn write(pattern: &Pattern) -> String {
+    let mut text = String::new();
+    if let Some(name) = &pattern.name {
+        writeln!(text, "!Name: {}", name).expect("Unable to write to string");
+    }
+    for comment in &pattern.comments {
+        writeln!(text, "!{}", comment).expect("Unable to write to string");
+    }
+    let mut rows = vec![vec!['.'; pattern.width]; pattern.height];
+    for &(x, y) in &pattern.cells {
+        rows[y as usize][x as usize] = 'O';
+    }
+    for row in rows {
+        text.extend(row);
+        text.push('\n');
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GLIDER: &str = "!Name: Glider\n!The smallest spaceship\n.O.\n..O\nOOO\n";
+
+    #[test]
+    fn reads_cells() {
+        let pattern = read(GLIDER).unwrap();
+        assert_eq!(pattern.name.as_deref(), Some("Glider"));
+        assert_eq!(pattern.comments, vec!["The smallest spaceship"]);
+        assert_eq!((pattern.width, pattern.height), (3, 3));
+        assert_eq!(pattern.cells, vec![(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)]);
+    }
+
+    #[test]
+    fn round_trips() {
+        let pattern = read(GLIDER).unwrap();
+        assert_eq!(write(&pattern), GLIDER);
+        assert_eq!(read(&write(&pattern)).unwrap(), pattern);
+    }
+
+    #[test]
+    fn keeps_inner_blank_rows_only() {
+        let pattern = read("O\n\n..*\n\n\n").unwrap();
+        assert_eq!((pattern.width, pattern.height), (3, 3));
+        assert_eq!(pattern.cells, vec![(0, 0), (2, 2)]);
+        assert_eq!(read(&write(&pattern)).unwrap(), pattern);
+    }
+
+    #[test]
+    fn rejects_unknown_characters() {
+        assert_eq!(
+            read("!Name: x\n.O.\n.X.\n"),
+            Err(ParseError::new(3, 2, "unexpected 'X'"))
+        );
+    }
+}
@@ -0,0 +1,382 @@
+use std::fmt::Write;
+
+use crate::{
+    pattern::{ParseError, Pattern},
+    rule::{Rule, RuleParseError},
+};
+
+// Body lines are kept under the 70 characters the format asks for
+const LINE_LENGTH: usize = 70;
+// Most live cells a pattern may have and the furthest a run may reach, so a bad
+// file can't exhaust memory. Runs also have to stay inside the header's size.
+const MAX_CELLS: usize = 1 << 24;
+const MAX_EXTENT: i64 = 1 << 40;
+
+pub fn read(text: &str) -> Result<Pattern, ParseError> {
+    let mut pattern = Pattern::default();
+    let mut size: Option<(usize, usize)> = None;
+    let mut cells = Vec::new();
+    let (mut x, mut y) = (0i64, 0i64);
+    let mut run: Option<(i64, usize)> = None;
+    let mut finished = false;
+
+    for (line_index, line) in text.lines().enumerate() {
+        let line_number = line_index + 1;
+        let trimmed = line.trim_start();
+        if finished {
+            break;
+        }
+        if let Some(comment) = trimmed.strip_prefix('#') {
+            let mut chars = comment.chars();
+            let kind = chars.next();
+            let text = chars.as_str().trim().to_string();
+            match kind {
+                Some('N') => pattern.name = Some(text),
+                Some('C' | 'c' | 'O') => pattern.comments.push(text),
+                Some('r') => {
+                    pattern.rule = Some(parse_rule(&text, line_number, 3)?);
+                }
+                _ => {}
+            }
+            continue;
+        }
+        if size.is_none() && cells.is_empty() && trimmed.starts_with('x') {
+            let (header_size, rule) = read_header(line, line_number)?;
+            size = Some(header_size);
+            if rule.is_some() {
+                pattern.rule = rule;
+            }
+            continue;
+        }
+        for (column_index, c) in line.char_indices() {
+            let column = column_index + 1;
+            match c {
+                '0'..='9' => {
+                    let digit = c.to_digit(10).expect("Expected digit") as i64;
+                    let (count, start) = run.unwrap_or((0, column));
+                    let count = count
+                        .checked_mul(10)
+                        .and_then(|n| n.checked_add(digit))
+                        .ok_or_else(|| ParseError::new(line_number, start, "run is too long"))?;
+                    run = Some((count, start));
+                }
+                'b' | '.' | 'o' | 'A' | '$' | '!' => {
+                    let (count, start) = run.take().unwrap_or((1, column));
+                    let error = |message| ParseError::new(line_number, start, message);
+                    let (max_x, max_y) = size.map_or((MAX_EXTENT, MAX_EXTENT), |(w, h)| {
+                        (
+                            w.min(MAX_EXTENT as usize) as i64,
+                            h.min(MAX_EXTENT as usize) as i64,
+                        )
+                    });
+                    match c {
+                        'b' | '.' | 'o' | 'A' => {
+                            let end = x.saturating_add(count);
+                            if end > max_x {
+                                return Err(error("run goes past the pattern width"));
+                            }
+                            if matches!(c, 'o' | 'A') {
+                                if y >= max_y {
+                                    return Err(error("run goes past the pattern height"));
+                                }
+                                if cells.len() + count as usize > MAX_CELLS {
+                                    return Err(error("pattern has too many cells"));
+                                }
+                                cells.extend((x..end).map(|cx| (cx, y)));
+                            }
+                            x = end;
+                        }
+                        '$' => {
+                            y = y.saturating_add(count);
+                            if y > max_y {
+                                return Err(error("run goes past the pattern height"));
+                            }
+                            x = 0;
+                        }
+                        _ => {
+                            finished = true;
+                            break;
+                        }
+                    }
+                }
+                c if c.is_whitespace() => {
+                    if let Some((_, start)) = run {
+                        return Err(ParseError::new(
+                            line_number,
+                            start,
+                            "run count must be followed by a tag",
+                        ));
+                    }
+                }
+                _ => {
+                    return Err(ParseError::new(
+                        line_number,
+                        column,
+                        format!("unexpected '{}'", c),
+                    ))
+                }
+            }
+        }
+    }
+    // The header's bounding box may include empty margins, so cells aren't shifted
+    let (width, height) = match size {
+        Some(size) => size,
+        None => (
+            cells
+                .iter()
+                .map(|&(x, _)| x as usize + 1)
+                .max()
+                .unwrap_or(0),
+            cells
+                .iter()
+                .map(|&(_, y)| y as usize + 1)
+                .max()
+                .unwrap_or(0),
+        ),
+    };
+    pattern.width = width;
+    pattern.height = height;
+    pattern.cells = cells;
+    Ok(pattern)
+}
+
+// Parses "x = m, y = n, rule = abc", returning the size and the rule if given
+fn read_header(
+    line: &str,
+    line_number: usize,
+) -> Result<((usize, usize), Option<Rule>), ParseError> {
+    let mut width = None;
+    let mut height = None;
+    let mut rule = None;
+    let mut offset = 0;
+    for item in line.split(',') {
+        let start = offset;
+        let column = start + 1 + (item.len() - item.trim_start().len());
+        let rest = &line[start..];
+        offset += item.len() + 1;
+        let Some((key, value)) = item.split_once('=') else {
+            return Err(ParseError::new(
+                line_number,
+                column,
+                "expected 'key = value'",
+            ));
+        };
+        let value_column = start + key.len() + 2 + (value.len() - value.trim_start().len());
+        let (key, value) = (key.trim(), value.trim());
+        match key {
+            "x" | "y" => {
+                let n = value.parse::<usize>().map_err(|_| {
+                    ParseError::new(
+                        line_number,
+                        value_column,
+                        format!("'{}' is not a valid size", value),
+                    )
+                })?;
+                if key == "x" {
+                    width = Some(n);
+                } else {
+                    height = Some(n);
+                }
+            }
+            // The rule is always last and may itself contain commas
+            "rule" => {
+                let value = rest.split_once('=').map_or("", |(_, v)| v).trim();
+                rule = Some(parse_rule(value, line_number, value_column)?);
+                break;
+            }
+            // Other keys such as Golly's generation count aren't needed
+            _ => {}
+        }
+    }
+    match (width, height) {
+        (Some(width), Some(height)) => Ok(((width, height), rule)),
+        _ => Err(ParseError::new(line_number, 1, "header needs both x and y")),
+    }
+}
+
+// Golly appends the bounded grid size after a ':', which doesn't apply here
+fn parse_rule(value: &str, line_number: usize, column: usize) -> Result<Rule, ParseError> {
+    let rule = value.split(':').next().unwrap_or(value);
+    rule.parse::<Rule>().map_err(|error| {
+        let column = match error {
+            RuleParseError::UnexpectedChar { index, .. }
+            | RuleParseError::DigitOutOfRange { index, .. }
+            | RuleParseError::DuplicateDigit { index, .. }
+            | RuleParseError::DuplicateSection { index, .. } => {
+                column + index + (rule.len() - rule.trim_start().len())
+            }
+            _ => column,
+        };
+        ParseError::new(line_number, column, format!("invalid rule: {}", error))
+    })
+}
+
+pub fn write(pattern: &Pattern) -> String {
+    let mut text = String::new();
+    if let Some(name) = &pattern.name {
+        writeln!(text, "#N {}", name).expect("Unable to write to string");
+    }
+    for comment in &pattern.comments {
+        writeln!(text, "#C {}", comment).expect("Unable to write to string");
+    }
+    write!(text, "x = {}, y = {}", pattern.width, pattern.height)
+        .expect("Unable to write to string");
+    if let Some(rule) = pattern.rule {
+        write!(text, ", rule = {}", rule).expect("Unable to write to string");
+    }
+    text.push('\n');
+
+    let mut cells = pattern.cells.clone();
+    cells.sort_by_key(|&(x, y)| (y, x));
+    cells.dedup();
+    // Runs of (count, tag), with trailing dead cells dropped from every row
+    let mut runs: Vec<(i64, char)> = Vec::new();
+    let mut push = |count: i64, tag: char| {
+        if count == 0 {
+            return;
+        }
+        match runs.last_mut() {
+            Some((n, t)) if *t == tag => *n += count,
+            _ => runs.push((count, tag)),
+        }
+    };
+    let (mut x, mut y) = (0, 0);
+    for (cx, cy) in cells {
+        if cy > y {
+            push(cy - y, '$');
+            y = cy;
+            x = 0;
+        }
+        push(cx - x, 'b');
+        push(1, 'o');
+        x = cx + 1;
+    }
+    runs.push((1, '!'));
+
+    let mut line = String::new();
+    for (count, tag) in runs {
+        let item = if count == 1 {
+            tag.to_string()
+        } else {
+            format!("{}{}", count, tag)
+        };
+        if line.len() + item.len() > LINE_LENGTH {
+            text.push_str(&line);
+            text.push('\n');
+            line.clear();
+        }
+        line.push_str(&item);
+    }
+    text.push_str(&line);
+    text.push('\n');
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error(text: &str) -> ParseError {
+        read(text).expect_err("Expected a parse error")
+    }
+
+    #[test]
+    fn reads_glider() {
+        let pattern = read("#N Glider\nx = 3, y = 3, rule = B3/S23\nbo$2bo$3o!").unwrap();
+        assert_eq!(pattern.name.as_deref(), Some("Glider"));
+        assert_eq!(pattern.rule, Some(Rule::conway()));
+        assert_eq!((pattern.width, pattern.height), (3, 3));
+        assert_eq!(pattern.cells, vec![(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)]);
+    }
+
+    #[test]
+    fn reads_runs_across_lines() {
+        let pattern = read("x = 12, y = 3\n1\n2o$\n\n$b.A!").unwrap();
+        assert_eq!(pattern.cells.len(), 13);
+        assert_eq!(pattern.cells[12], (2, 2));
+    }
+
+    #[test]
+    fn keeps_header_margins() {
+        let pattern = read("x = 5, y = 4\n$bo!").unwrap();
+        assert_eq!((pattern.width, pattern.height), (5, 4));
+        assert_eq!(pattern.cells, vec![(1, 1)]);
+    }
+
+    #[test]
+    fn reads_without_header() {
+        let pattern = read("3o$o!").unwrap();
+        assert_eq!((pattern.width, pattern.height), (3, 2));
+    }
+
+    #[test]
+    fn round_trips() {
+        let text = "x = 36, y = 9, rule = B3/S23\n24bo$22bobo$12b2o6b2o12b2o$11bo3bo4b2o12b2o$2o8bo5bo3b2o$2o8bo3bob2o4bobo$10bo5bo7bo$11bo3bo$12b2o!";
+        let pattern = read(text).unwrap();
+        assert_eq!(read(&write(&pattern)).unwrap(), pattern);
+        for line in write(&pattern).lines() {
+            assert!(line.len() <= LINE_LENGTH);
+        }
+    }
+
+    #[test]
+    fn rejects_malformed_input() {
+        assert_eq!(
+            error("x = 3\n3o!"),
+            ParseError::new(1, 1, "header needs both x and y")
+        );
+        assert_eq!(
+            error("x = a, y = 1\n3o!"),
+            ParseError::new(1, 5, "'a' is not a valid size")
+        );
+        assert_eq!(
+            error("x = 3, y = 1\n2z!"),
+            ParseError::new(2, 2, "unexpected 'z'")
+        );
+        assert_eq!(
+            error("x = 3, y = 1\n2 o!"),
+            ParseError::new(2, 1, "run count must be followed by a tag")
+        );
+        assert_eq!(error("x = 3, y = 1, rule = B9/S23\n3o!").line, 1);
+    }
+
+    #[test]
+    fn rejects_runs_outside_the_header() {
+        assert_eq!(
+            error("x = 3, y = 2\nbo$b3o!"),
+            ParseError::new(2, 5, "run goes past the pattern width")
+        );
+        assert_eq!(
+            error("x = 3, y = 2\n2$o!"),
+            ParseError::new(2, 3, "run goes past the pattern height")
+        );
+        assert_eq!(
+            error("x = 3, y = 2\n3$!"),
+            ParseError::new(2, 1, "run goes past the pattern height")
+        );
+    }
+
+    #[test]
+    fn rejects_oversized_runs() {
+        assert_eq!(
+            error("x = 10, y = 1\n999999999999o!"),
+            ParseError::new(2, 1, "run goes past the pattern width")
+        );
+        assert_eq!(
+            error("999999999999o!"),
+            ParseError::new(1, 1, "pattern has too many cells")
+        );
+        assert_eq!(
+            error("x = 9999999999999, y = 1\n999999999999o!"),
+            ParseError::new(2, 1, "pattern has too many cells")
+        );
+        assert_eq!(
+            error("9223372036854775807b9223372036854775807o!"),
+            ParseError::new(1, 1, "run goes past the pattern width")
+        );
+        assert_eq!(
+            error("99999999999999999999o!"),
+            ParseError::new(1, 1, "run is too long")
+        );
+    }
+}
@@ -0,0 +1,323 @@
+use std::{error::Error, fmt, str::FromStr};
+
+// Life-like rule, indexed by live neighbour count (0..=8)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Rule {
+    birth: [bool; 9],
+    survival: [bool; 9],
+}
+
+impl Rule {
+    pub fn new(birth: &[u8], survival: &[u8]) -> Self {
+        let mut rule = Self {
+            birth: [false; 9],
+            survival: [false; 9],
+        };
+        for &n in birth.iter().filter(|&&n| n <= 8) {
+            rule.birth[n as usize] = true;
+        }
+        for &n in survival.iter().filter(|&&n| n <= 8) {
+            rule.survival[n as usize] = true;
+        }
+        rule
+    }
+
+    pub fn conway() -> Self {
+        Self::new(&[3], &[2, 3])
+    }
+
+    pub fn next(&self, alive: bool, neighbours: u32) -> bool {
+        if alive {
+            self.survival[neighbours as usize]
+        } else {
+            self.birth[neighbours as usize]
+        }
+    }
+
+    pub fn birth(&self, neighbours: u32) -> bool {
+        self.birth[neighbours as usize]
+    }
+
+    pub fn survival(&self, neighbours: u32) -> bool {
+        self.survival[neighbours as usize]
+    }
+}
+
+impl Default for Rule {
+    fn default() -> Self {
+        Self::conway()
+    }
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "B")?;
+        for n in (0..9).filter(|&n| self.birth[n]) {
+            write!(f, "{}", n)?;
+        }
+        write!(f, "/S")?;
+        for n in (0..9).filter(|&n| self.survival[n]) {
+            write!(f, "{}", n)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RuleParseError {
+    Empty,
+    UnexpectedChar { found: char, index: usize },
+    DigitOutOfRange { found: char, index: usize },
+    DuplicateDigit { found: char, index: usize },
+    DuplicateSection { section: char, index: usize },
+    MissingSection { section: char },
+    MissingSeparator,
+}
+
+impl fmt::Display for RuleParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "rule is empty"),
+            Self::UnexpectedChar { found, index } => {
+                write!(
+                    f,
+                    "unexpected character '{}' at position {}",
+                    found,
+                    index + 1
+                )
+            }
+            Self::DigitOutOfRange { found, index } => write!(
+                f,
+                "neighbour count '{}' at position {} is out of range 0-8",
+                found,
+                index + 1
+            ),
+            Self::DuplicateDigit { found, index } => write!(
+                f,
+                "neighbour count '{}' at position {} is repeated",
+                found,
+                index + 1
+            ),
+            Self::DuplicateSection { section, index } => write!(
+                f,
+                "section '{}' at position {} is repeated",
+                section,
+                index + 1
+            ),
+            Self::MissingSection { section } => write!(f, "missing '{}' section", section),
+            Self::MissingSeparator => write!(f, "expected '/' between survival and birth"),
+        }
+    }
+}
+
+impl Error for RuleParseError {}
+
+impl FromStr for Rule {
+    type Err = RuleParseError;
+
+    // Accepts "B3/S23", "S23/B3", "B3S23" and the older survival/birth form "23/3"
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.is_empty() {
+            return Err(RuleParseError::Empty);
+        }
+        if s.chars().any(|c| c.is_ascii_alphabetic()) {
+            parse_bs(s)
+        } else {
+            parse_sb(s)
+        }
+    }
+}
+
+fn parse_bs(s: &str) -> Result<Rule, RuleParseError> {
+    let mut rule = Rule::new(&[], &[]);
+    let mut seen_birth = false;
+    let mut seen_survival = false;
+    // None until a 'B' or 'S' marker has been read
+    let mut section: Option<char> = None;
+    for (index, c) in s.char_indices() {
+        let upper = c.to_ascii_uppercase();
+        match upper {
+            'B' | 'S' => {
+                let seen = if upper == 'B' {
+                    &mut seen_birth
+                } else {
+                    &mut seen_survival
+                };
+                if *seen {
+                    return Err(RuleParseError::DuplicateSection {
+                        section: upper,
+                        index,
+                    });
+                }
+                *seen = true;
+                section = Some(upper);
+            }
+            '/' if section.is_some() => {}
+            '0'..='9' => {
+                let counts = match section {
+                    Some('B') => &mut rule.birth,
+                    Some(_) => &mut rule.survival,
+                    None => return Err(RuleParseError::UnexpectedChar { found: c, index }),
+                };
+                set_count(counts, c, index)?;
+            }
+            _ => return Err(RuleParseError::UnexpectedChar { found: c, index }),
+        }
+    }
+    if !seen_birth {
+        return Err(RuleParseError::MissingSection { section: 'B' });
+    }
+    if !seen_survival {
+        return Err(RuleParseError::MissingSection { section: 'S' });
+    }
+    Ok(rule)
+}
+
+fn parse_sb(s: &str) -> Result<Rule, RuleParseError> {
+    let mut rule = Rule::new(&[], &[]);
+    let slash = s.find('/').ok_or(RuleParseError::MissingSeparator)?;
+    for (index, c) in s.char_indices() {
+        match c {
+            '/' if index == slash => {}
+            '0'..='9' if index < slash => set_count(&mut rule.survival, c, index)?,
+            '0'..='9' => set_count(&mut rule.birth, c, index)?,
+            _ => return Err(RuleParseError::UnexpectedChar { found: c, index }),
+        }
+    }
+    Ok(rule)
+}
+
+fn set_count(counts: &mut [bool; 9], c: char, index: usize) -> Result<(), RuleParseError> {
+    let n = c.to_digit(10).expect("Expected digit") as usize;
+    if n > 8 {
+        return Err(RuleParseError::DigitOutOfRange { found: c, index });
+    }
+    if counts[n] {
+        return Err(RuleParseError::DuplicateDigit { found: c, index });
+    }
+    counts[n] = true;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_birth_survival() {
+        assert_eq!("B3/S23".parse(), Ok(Rule::conway()));
+        assert_eq!("S23/B3".parse(), Ok(Rule::conway()));
+        assert_eq!("B3S23".parse(), Ok(Rule::conway()));
+        assert_eq!(" B3/S23 ".parse(), Ok(Rule::conway()));
+    }
+
+    #[test]
+    fn is_case_insensitive() {
+        assert_eq!("b36/s23".parse(), Ok(Rule::new(&[3, 6], &[2, 3])));
+    }
+
+    #[test]
+    fn parses_survival_birth() {
+        assert_eq!("23/3".parse(), Ok(Rule::conway()));
+        assert_eq!("23/36".parse(), Ok(Rule::new(&[3, 6], &[2, 3])));
+        assert_eq!("/2".parse(), Ok(Rule::new(&[2], &[])));
+        assert_eq!("23".parse::<Rule>(), Err(RuleParseError::MissingSeparator));
+    }
+
+    #[test]
+    fn allows_empty_sections() {
+        assert_eq!("B/S".parse(), Ok(Rule::new(&[], &[])));
+        assert_eq!("B3/S".parse(), Ok(Rule::new(&[3], &[])));
+        assert_eq!(
+            "B/S012345678".parse(),
+            Ok(Rule::new(&[], &[0, 1, 2, 3, 4, 5, 6, 7, 8]))
+        );
+    }
+
+    #[test]
+    fn rejects_duplicates() {
+        assert_eq!(
+            "B33/S23".parse::<Rule>(),
+            Err(RuleParseError::DuplicateDigit {
+                found: '3',
+                index: 2
+            })
+        );
+        assert_eq!(
+            "22/3".parse::<Rule>(),
+            Err(RuleParseError::DuplicateDigit {
+                found: '2',
+                index: 1
+            })
+        );
+        assert_eq!(
+            "B3/S23/B3".parse::<Rule>(),
+            Err(RuleParseError::DuplicateSection {
+                section: 'B',
+                index: 7
+            })
+        );
+    }
+
+    #[test]
+    fn rejects_nine() {
+        assert_eq!(
+            "B39/S23".parse::<Rule>(),
+            Err(RuleParseError::DigitOutOfRange {
+                found: '9',
+                index: 2
+            })
+        );
+        assert_eq!(
+            "9/3".parse::<Rule>(),
+            Err(RuleParseError::DigitOutOfRange {
+                found: '9',
+                index: 0
+            })
+        );
+    }
+
+    #[test]
+    fn rejects_garbage() {
+        assert_eq!("".parse::<Rule>(), Err(RuleParseError::Empty));
+        assert_eq!("   ".parse::<Rule>(), Err(RuleParseError::Empty));
+        assert_eq!(
+            "B3/X23".parse::<Rule>(),
+            Err(RuleParseError::UnexpectedChar {
+                found: 'X',
+                index: 3
+            })
+        );
+        assert_eq!(
+            "3B/S23".parse::<Rule>(),
+            Err(RuleParseError::UnexpectedChar {
+                found: '3',
+                index: 0
+            })
+        );
+        assert_eq!(
+            "B3".parse::<Rule>(),
+            Err(RuleParseError::MissingSection { section: 'S' })
+        );
+        assert_eq!(
+            "S23".parse::<Rule>(),
+            Err(RuleParseError::MissingSection { section: 'B' })
+        );
+        assert_eq!(
+            "2-3/3".parse::<Rule>(),
+            Err(RuleParseError::UnexpectedChar {
+                found: '-',
+                index: 1
+            })
+        );
+    }
+
+    #[test]
+    fn display_round_trips() {
+        for text in ["B3/S23", "B36/S23", "B/S", "B0/S8", "B2/S"] {
+            let rule: Rule = text.parse().unwrap();
+            assert_eq!(rule.to_string(), text);
+        }
+    }
+}
@@ -0,0 +1,163 @@
+// Cells covered by drawing operations, independent of any engine
+use std::{collections::HashSet, fmt};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum BrushShape {
+    #[default]
+    Square,
+    Circle,
+}
+
+impl BrushShape {
+    pub fn next(self) -> Self {
+        match self {
+            BrushShape::Square => BrushShape::Circle,
+            BrushShape::Circle => BrushShape::Square,
+        }
+    }
+}
+
+impl fmt::Display for BrushShape {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BrushShape::Square => write!(f, "square"),
+            BrushShape::Circle => write!(f, "circle"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Brush {
+    // Width in cells
+    pub size: u32,
+    pub shape: BrushShape,
+}
+
+impl Default for Brush {
+    fn default() -> Self {
+        Self {
+            size: 1,
+            shape: BrushShape::Square,
+        }
+    }
+}
+
+impl Brush {
+    // Offsets of the cells under the brush when it's over (0, 0). Even sizes have
+    // one more cell right of and below the centre than before it.
+    pub fn footprint(&self) -> Vec<[i64; 2]> {
+        let size = self.size.max(1) as i64;
+        let start = -(size - 1) / 2;
+        let centre = (size - 1) as f64 / 2.0;
+        let radius = size as f64 / 2.0;
+        let mut cells = Vec::new();
+        for y in 0..size {
+            for x in 0..size {
+                let (dx, dy) = (x as f64 - centre, y as f64 - centre);
+                if self.shape == BrushShape::Square || dx * dx + dy * dy <= radius * radius {
+                    cells.push([start + x, start + y]);
+                }
+            }
+        }
+        cells
+    }
+}
+
+// Cells on the straight line between two cells, both ends included (Bresenham)
+pub fn line(from: [i64; 2], to: [i64; 2]) -> Vec<[i64; 2]> {
+    let [mut x, mut y] = from;
+    let dx = (to[0] - x).abs();
+    let dy = -(to[1] - y).abs();
+    let step_x = if x < to[0] { 1 } else { -1 };
+    let step_y = if y < to[1] { 1 } else { -1 };
+    let mut error = dx + dy;
+    let mut cells = Vec::with_capacity(dx.max(-dy) as usize + 1);
+    loop {
+        cells.push([x, y]);
+        if [x, y] == to {
+            return cells;
+        }
+        let doubled = 2 * error;
+        if doubled >= dy {
+            error += dy;
+            x += step_x;
+        }
+        if doubled <= dx {
+            error += dx;
+            y += step_y;
+        }
+    }
+}
+
+// Cells of the rectangle with opposite corners at the given cells
+pub fn rectangle(from: [i64; 2], to: [i64; 2], filled: bool) -> Vec<[i64; 2]> {
+    let [min_x, min_y] = [from[0].min(to[0]), from[1].min(to[1])];
+    let [max_x, max_y] = [from[0].max(to[0]), from[1].max(to[1])];
+    let mut cells = Vec::new();
+    for y in min_y..=max_y {
+        for x in min_x..=max_x {
+            if filled || x == min_x || x == max_x || y == min_y || y == max_y {
+                cells.push([x, y]);
+            }
+        }
+    }
+    cells
+}
+
+// Cells of the ellipse filling the rectangle with opposite corners at the given
+// cells. A cell is inside when its centre is, the outline is every inside cell
+// with a neighbour outside.
+pub fn ellipse(from: [i64; 2], to: [i64; 2], filled: bool) -> Vec<[i64; 2]> {
+    let [min_x, min_y] = [from[0].min(to[0]), from[1].min(to[1])];
+    let [max_x, max_y] = [from[0].max(to[0]), from[1].max(to[1])];
+    let centre = [(min_x + max_x) as f64 / 2.0, (min_y + max_y) as f64 / 2.0];
+    let radius = [
+        (max_x - min_x) as f64 / 2.0 + 0.5,
+        (max_y - min_y) as f64 / 2.0 + 0.5,
+    ];
+    let inside = |x: i64, y: i64| {
+        let dx = (x as f64 - centre[0]) / radius[0];
+        let dy = (y as f64 - centre[1]) / radius[1];
+        dx * dx + dy * dy <= 1.0
+    };
+    let mut cells = Vec::new();
+    for y in min_y..=max_y {
+        for x in min_x..=max_x {
+            if inside(x, y)
+                && (filled
+                    || !inside(x - 1, y)
+                    || !inside(x + 1, y)
+                    || !inside(x, y - 1)
+                    || !inside(x, y + 1))
+            {
+                cells.push([x, y]);
+            }
+        }
+    }
+    cells
+}
+
+// Cells reachable from start through orthogonal neighbours that match, without
+// leaving the inclusive bounds [min_x, min_y, max_x, max_y]
+pub fn flood(
+    start: [i64; 2],
+    bounds: [i64; 4],
+    matches: impl Fn(i64, i64) -> bool,
+) -> Vec<[i64; 2]> {
+    let [min_x, min_y, max_x, max_y] = bounds;
+    let within = |x: i64, y: i64| x >= min_x && x <= max_x && y >= min_y && y <= max_y;
+    if !within(start[0], start[1]) || !matches(start[0], start[1]) {
+        return Vec::new();
+    }
+    let mut seen = HashSet::from([start]);
+    let mut pending = vec![start];
+    while let Some([x, y]) = pending.pop() {
+        for next in [[x - 1, y], [x + 1, y], [x, y - 1], [x, y + 1]] {
+            if within(next[0], next[1]) && !seen.contains(&next) && matches(next[0], next[1]) {
+                seen.insert(next);
+                pending.push(next);
+            }
+        }
+    }
+    seen.into_iter().collect()
+}
@@ -0,0 +1,148 @@
+use std::{fmt, str::FromStr};
+
+use rand::Rng;
+use rand_chacha::{rand_core::SeedableRng, ChaCha8Rng};
+
+// Symmetry a soup is made with, as used by soup searchers. Only one fundamental
+// region is random, the rest is rotated or mirrored copies of it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Symmetry {
+    None,
+    // Half turn about the centre
+    C2,
+    // Mirrored left to right
+    D2,
+    // Quarter turns about the centre
+    C4,
+    // Mirrored left to right and top to bottom
+    D4,
+    // Quarter turns and both diagonals
+    D8,
+}
+
+impl Symmetry {
+    pub const ALL: [Symmetry; 6] = [
+        Symmetry::None,
+        Symmetry::C2,
+        Symmetry::D2,
+        Symmetry::C4,
+        Symmetry::D4,
+        Symmetry::D8,
+    ];
+
+    pub fn next(self) -> Self {
+        let index = Self::ALL.iter().position(|&s| s == self).unwrap_or(0);
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+
+    // Quarter turns and diagonals only map a square onto itself
+    fn needs_square(self) -> bool {
+        matches!(self, Symmetry::C4 | Symmetry::D8)
+    }
+
+    // Every cell of a cols x rows rectangle that (x, y) is copied to, including itself
+    fn images(self, x: usize, y: usize, cols: usize, rows: usize) -> Vec<(usize, usize)> {
+        let (fx, fy) = (cols - 1 - x, rows - 1 - y);
+        match self {
+            Symmetry::None => vec![(x, y)],
+            Symmetry::C2 => vec![(x, y), (fx, fy)],
+            Symmetry::D2 => vec![(x, y), (fx, y)],
+            Symmetry::C4 => vec![(x, y), (fy, x), (fx, fy), (y, fx)],
+            Symmetry::D4 => vec![(x, y), (fx, y), (x, fy), (fx, fy)],
+            Symmetry::D8 => vec![
+                (x, y),
+                (fy, x),
+                (fx, fy),
+                (y, fx),
+                (y, x),
+                (fx, y),
+                (x, fy),
+                (fy, fx),
+            ],
+        }
+    }
+}
+
+impl fmt::Display for Symmetry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Symmetry::None => write!(f, "None"),
+            Symmetry::C2 => write!(f, "C2"),
+            Symmetry::D2 => write!(f, "D2"),
+            Symmetry::C4 => write!(f, "C4"),
+            Symmetry::D4 => write!(f, "D4"),
+            Symmetry::D8 => write!(f, "D8"),
+        }
+    }
+}
+
+impl FromStr for Symmetry {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|symmetry| symmetry.to_string().eq_ignore_ascii_case(s.trim()))
+            .ok_or_else(|| format!("unknown symmetry '{}'", s))
+    }
+}
+
+// Settings for a random fill. The same seed and density always give the same
+// cells, ChaCha8 is used over StdRng because its output is fixed across releases.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Soup {
+    pub seed: u64,
+    // Percentage of live cells, 0 to 100
+    pub density: u32,
+    pub symmetry: Symmetry,
+}
+
+impl Default for Soup {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            density: 50,
+            symmetry: Symmetry::None,
+        }
+    }
+}
+
+impl Soup {
+    // Whether each cell of a cols x rows rectangle is alive, row by row. C4 and D8
+    // soups fill the largest centred square and leave the rest dead.
+    pub fn cells(&self, cols: usize, rows: usize) -> Vec<bool> {
+        let (width, height) = if self.symmetry.needs_square() {
+            (cols.min(rows), cols.min(rows))
+        } else {
+            (cols, rows)
+        };
+        let mut rng = ChaCha8Rng::seed_from_u64(self.seed);
+        let density = self.density.min(100);
+        // Cells are drawn in row order for the first of each set of images and copied
+        // for the rest, so without symmetry the output is the same as a plain fill
+        let mut region = vec![false; width * height];
+        for y in 0..height {
+            for x in 0..width {
+                let first = self
+                    .symmetry
+                    .images(x, y, width, height)
+                    .into_iter()
+                    .map(|(ix, iy)| iy * width + ix)
+                    .min()
+                    .unwrap_or(y * width + x);
+                region[y * width + x] = if first == y * width + x {
+                    rng.gen_ratio(density, 100)
+                } else {
+                    region[first]
+                };
+            }
+        }
+
+        let (left, top) = ((cols - width) / 2, (rows - height) / 2);
+        let mut cells = vec![false; cols * rows];
+        for (i, alive) in region.into_iter().enumerate() {
+            cells[(top + i / width) * cols + left + i % width] = alive;
+        }
+        cells
+    }
+}
@@ -0,0 +1,133 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::rule::Rule;
+
+// Unbounded universe that only stores live cells. Rules with B0 would fill the
+// infinite plane, so birth on zero neighbours is never applied here.
+#[derive(Default)]
+pub struct SparseBoard {
+    cells: HashSet<(i64, i64)>,
+    rule: Rule,
+}
+
+impl SparseBoard {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn rule(&self) -> Rule {
+        self.rule
+    }
+
+    pub fn get(&self, x: i64, y: i64) -> bool {
+        self.cells.contains(&(x, y))
+    }
+
+    pub fn set(&mut self, x: i64, y: i64, alive: bool) {
+        if alive {
+            self.cells.insert((x, y));
+        } else {
+            self.cells.remove(&(x, y));
+        }
+    }
+
+    pub fn live_cells(&self) -> impl Iterator<Item = (i64, i64)> + '_ {
+        self.cells.iter().copied()
+    }
+
+    pub fn step(&mut self) {
+        // Only cells next to a live cell can have a non-zero count
+        let mut counts: HashMap<(i64, i64), u32> = HashMap::with_capacity(self.cells.len() * 8);
+        for &(x, y) in &self.cells {
+            counts.entry((x, y)).or_insert(0);
+            for dy in -1..=1 {
+                for dx in -1..=1 {
+                    if dx != 0 || dy != 0 {
+                        *counts.entry((x + dx, y + dy)).or_insert(0) += 1;
+                    }
+                }
+            }
+        }
+        self.cells = counts
+            .into_iter()
+            .filter(|&(cell, neighbours)| {
+                let alive = self.cells.contains(&cell);
+                (alive || neighbours > 0) && self.rule.next(alive, neighbours)
+            })
+            .map(|(cell, _)| cell)
+            .collect();
+    }
+
+    pub fn set_rule(&mut self, rule: Rule) {
+        self.rule = rule;
+    }
+
+    pub fn clear(&mut self) {
+        self.cells.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::Board;
+    use crate::soup::Soup;
+
+    // A small soup in the middle of a board big enough that nothing reaches the
+    // edge, even growing at the speed of light
+    fn compare(rule: Rule, seed: u64) {
+        const SIZE: usize = 160;
+        const SOUP: usize = 24;
+        const OFFSET: usize = (SIZE - SOUP) / 2;
+        let mut board = Board::new(SIZE, SIZE);
+        let mut sparse = SparseBoard::new();
+        board.set_rule(rule);
+        sparse.set_rule(rule);
+        let soup = Soup {
+            seed,
+            density: 40,
+            ..Soup::default()
+        };
+        for (i, alive) in soup.cells(SOUP, SOUP).into_iter().enumerate() {
+            let [x, y] = [i % SOUP, i / SOUP];
+            board.set(x + OFFSET, y + OFFSET, alive);
+            sparse.set(x as i64, y as i64, alive);
+        }
+        for generation in 1..=40 {
+            board.step();
+            sparse.step();
+            let expected: HashSet<(i64, i64)> = board
+                .live_cells()
+                .map(|(x, y)| (x as i64 - OFFSET as i64, y as i64 - OFFSET as i64))
+                .collect();
+            let actual: HashSet<(i64, i64)> = sparse.live_cells().collect();
+            assert_eq!(
+                expected, actual,
+                "{} seed {} generation {}",
+                rule, seed, generation
+            );
+        }
+    }
+
+    #[test]
+    fn matches_board() {
+        for seed in 0..4 {
+            compare(Rule::default(), seed);
+        }
+    }
+
+    #[test]
+    fn matches_board_on_other_rules() {
+        for rule in ["B36/S23", "B3678/S34678", "B2/S"] {
+            compare(rule.parse().expect("Unable to parse rule"), 7);
+        }
+    }
+
+    #[test]
+    fn never_applies_b0() {
+        let mut sparse = SparseBoard::new();
+        sparse.set_rule("B0/S8".parse().expect("Unable to parse rule"));
+        sparse.step();
+        assert_eq!(sparse.live_cells().count(), 0);
+    }
+}
@@ -0,0 +1,24 @@
+// Fixtures shared by the engine tests
+use crate::soup::Soup;
+
+// Sizes for the parallel tests, wide and narrow so bands split in awkward places
+pub const PARALLEL_SIZES: [[usize; 2]; 5] = [[130, 3], [7, 1], [37, 97], [64, 64], [1, 23]];
+
+// Soup seeded by the board size so every size gets a different but repeatable fill
+pub fn soup(cols: usize, rows: usize) -> Vec<bool> {
+    Soup {
+        seed: (cols * 1000 + rows) as u64,
+        density: 40,
+        ..Soup::default()
+    }
+    .cells(cols, rows)
+}
+
+// Runs a test on a fixed thread count so there are several bands whatever the machine
+pub fn with_pool(test: impl FnOnce() + Send) {
+    rayon::ThreadPoolBuilder::new()
+        .num_threads(5)
+        .build()
+        .expect("Unable to build thread pool")
+        .install(test);
+}
@@ -0,0 +1,195 @@
+use std::fmt;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Topology {
+    // Everything beyond the edge is dead
+    #[default]
+    Bounded,
+    Torus,
+    // Left and right edges joined
+    HorizontalCylinder,
+    // Top and bottom edges joined
+    VerticalCylinder,
+    // Left and right edges joined, top and bottom joined with a half twist
+    KleinBottle,
+    // Both pairs of edges joined with a half twist (projective plane)
+    CrossSurface,
+}
+
+impl Topology {
+    pub const ALL: [Topology; 6] = [
+        Topology::Bounded,
+        Topology::Torus,
+        Topology::HorizontalCylinder,
+        Topology::VerticalCylinder,
+        Topology::KleinBottle,
+        Topology::CrossSurface,
+    ];
+
+    // [wrap x, flip y when wrapping x, wrap y, flip x when wrapping y]
+    fn joins(self) -> [bool; 4] {
+        match self {
+            Topology::Bounded => [false, false, false, false],
+            Topology::Torus => [true, false, true, false],
+            Topology::HorizontalCylinder => [true, false, false, false],
+            Topology::VerticalCylinder => [false, false, true, false],
+            Topology::KleinBottle => [true, false, true, true],
+            Topology::CrossSurface => [true, true, true, true],
+        }
+    }
+
+    // Maps a coordinate up to one board width/height outside the board back onto it,
+    // None if it falls off a dead edge
+    pub fn wrap(self, x: i64, y: i64, cols: usize, rows: usize) -> Option<(usize, usize)> {
+        let [wrap_x, flip_y, wrap_y, flip_x] = self.joins();
+        let (cols, rows) = (cols as i64, rows as i64);
+        let outside_x = x < 0 || x >= cols;
+        let outside_y = y < 0 || y >= rows;
+        // The corners of a cross-surface are singular points with no consistent neighbour
+        if outside_x && outside_y && flip_x && flip_y {
+            return None;
+        }
+        let (mut x, mut y) = (x, y);
+        if outside_x {
+            if !wrap_x {
+                return None;
+            }
+            x = x.rem_euclid(cols);
+            if flip_y {
+                y = rows - 1 - y;
+            }
+        }
+        if outside_y {
+            if !wrap_y {
+                return None;
+            }
+            y = y.rem_euclid(rows);
+            if flip_x {
+                x = cols - 1 - x;
+            }
+        }
+        Some((x as usize, y as usize))
+    }
+}
+
+impl fmt::Display for Topology {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Topology::Bounded => "Bounded",
+            Topology::Torus => "Torus",
+            Topology::HorizontalCylinder => "H. cylinder",
+            Topology::VerticalCylinder => "V. cylinder",
+            Topology::KleinBottle => "Klein bottle",
+            Topology::CrossSurface => "Cross-surface",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::Board;
+
+    // Where each of the given points ends up on a 4x3 board
+    fn wraps(topology: Topology, points: &[[i64; 2]]) -> Vec<Option<(usize, usize)>> {
+        points
+            .iter()
+            .map(|&[x, y]| topology.wrap(x, y, 4, 3))
+            .collect()
+    }
+
+    // Just past the left, right, top and bottom edges, then the top left and
+    // bottom right corners
+    const OUTSIDE: [[i64; 2]; 6] = [[-1, 0], [4, 1], [1, -1], [2, 3], [-1, -1], [4, 3]];
+
+    #[test]
+    fn keeps_cells_on_the_board() {
+        for topology in Topology::ALL {
+            for (x, y) in [(0, 0), (3, 0), (0, 2), (3, 2), (1, 1)] {
+                assert_eq!(topology.wrap(x, y, 4, 3), Some((x as usize, y as usize)));
+            }
+        }
+    }
+
+    #[test]
+    fn bounded_edges_are_dead() {
+        assert_eq!(wraps(Topology::Bounded, &OUTSIDE), vec![None; 6]);
+    }
+
+    #[test]
+    fn torus_joins_opposite_edges() {
+        assert_eq!(
+            wraps(Topology::Torus, &OUTSIDE),
+            vec![
+                Some((3, 0)),
+                Some((0, 1)),
+                Some((1, 2)),
+                Some((2, 0)),
+                Some((3, 2)),
+                Some((0, 0))
+            ]
+        );
+    }
+
+    #[test]
+    fn cylinders_join_one_pair_of_edges() {
+        assert_eq!(
+            wraps(Topology::HorizontalCylinder, &OUTSIDE),
+            vec![Some((3, 0)), Some((0, 1)), None, None, None, None]
+        );
+        assert_eq!(
+            wraps(Topology::VerticalCylinder, &OUTSIDE),
+            vec![None, None, Some((1, 2)), Some((2, 0)), None, None]
+        );
+    }
+
+    #[test]
+    fn klein_bottle_mirrors_top_and_bottom() {
+        assert_eq!(
+            wraps(Topology::KleinBottle, &OUTSIDE),
+            vec![
+                Some((3, 0)),
+                Some((0, 1)),
+                Some((2, 2)),
+                Some((1, 0)),
+                Some((0, 2)),
+                Some((3, 0))
+            ]
+        );
+    }
+
+    #[test]
+    fn cross_surface_mirrors_both_pairs() {
+        assert_eq!(
+            wraps(Topology::CrossSurface, &OUTSIDE),
+            vec![
+                Some((3, 2)),
+                Some((0, 1)),
+                Some((2, 2)),
+                Some((1, 0)),
+                None,
+                None
+            ]
+        );
+    }
+
+    #[test]
+    fn glider_circles_a_torus() {
+        let mut board = Board::new(10, 12);
+        board.set_topology(Topology::Torus);
+        for (x, y) in [(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)] {
+            board.set(x, y, true);
+        }
+        let start: Vec<_> = board.live_cells().collect();
+        // A cell diagonally every 4 generations, lcm(10, 12) cells to come back round
+        for generation in 1..=240 {
+            board.step();
+            assert_eq!(board.live_cells().count(), 5);
+            if generation < 240 {
+                assert_ne!(board.live_cells().collect::<Vec<_>>(), start);
+            }
+        }
+        assert_eq!(board.live_cells().collect::<Vec<_>>(), start);
+    }
+}
@@ -36,7 +36,7 @@ impl HGroup {
         let item_width = width / items.len() as f64;
         for (i, item) in items.iter_mut().enumerate() {
             item.set_size(item_width, item_height);
-            item.set_pos(x + i as f64 * item_height, y);
+            item.set_pos(x + i as f64 * item_width, y);
         }
 
         Self {